@@ -6,6 +6,7 @@ use permguard::az_req::action_builder::ActionBuilder;
 use permguard::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
 use permguard::az_req::az_request_builder::AzRequestBuilder;
 use permguard::az_req::context_builder::ContextBuilder;
+use permguard::az_req::entity_builder::EntityBuilder;
 use permguard::az_req::evaluation_builder::EvaluationBuilder;
 use permguard::az_req::model::AzRequest;
 use permguard::az_req::principal_builder::PrincipalBuilder;
@@ -30,7 +31,7 @@ async fn main()  -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn atomic_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn Error>>>{
-    let endpoint = AzEndpoint::new("http".to_string(), 9094, "localhost".to_string());
+    let endpoint = AzEndpoint::parse("http://localhost:9094").map_err(|e| Err(e.into()))?;
     let config = AzConfig::new().with_endpoint(Some(endpoint));
     let client = AzClient::new(config);
 
@@ -39,18 +40,9 @@ async fn atomic_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn
         .with_type("user")
         .build();
 
-    let entity = {
-        let mut map = HashMap::new();
-        map.insert("uid".to_string(), json!({
-            "type": "PharmaAuthZFlow::Platform::BranchInfo",
-            "id": "subscription"
-        }));
-        map.insert("attrs".to_string(), json!({"active": true}));
-        map.insert("parents".to_string(), json!([]));
-        Some(map)
-    };
-
-    let entities = vec![entity];
+    let entity = EntityBuilder::new("PharmaAuthZFlow::Platform::BranchInfo", "subscription")
+        .with_attribute("active", json!(true))
+        .build();
 
     let request = AzAtomicRequestBuilder::new(
         189106194833,
@@ -66,7 +58,7 @@ async fn atomic_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn
         .with_subject_source("keycloak")
         .with_resource_id("e3a786fd07e24bfa95ba4341d3695ae8")
         .with_resource_property("isEnabled", json!(true))
-        .with_entities_map("cedar", entities)
+        .with_entity(entity)
         .with_action_property("isEnabled", json!(true))
         .with_context_property("isSubscriptionActive", json!(true))
         .with_context_property("time", json!("2025-01-23T16:17:46+00:00"))
@@ -112,8 +104,9 @@ async fn atomic_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn
 }
 
 
+#[allow(dead_code)]
 async fn json_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn Error>>>{
-    let endpoint = AzEndpoint::new("http".to_string(), 9094, "localhost".to_string());
+    let endpoint = AzEndpoint::parse("http://localhost:9094").map_err(|e| Err(e.into()))?;
     let config = AzConfig::new().with_endpoint(Some(endpoint));
     let client = AzClient::new(config);
 
@@ -176,8 +169,9 @@ async fn json_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn Er
     Ok(Ok(()))
 }
 
+#[allow(dead_code)]
 async fn first_test() -> Result<Result<(), Box<dyn Error>>, Result<(), Box<dyn Error>>> {
-    let endpoint = AzEndpoint::new("http".to_string(), 9094, "localhost".to_string());
+    let endpoint = AzEndpoint::parse("http://localhost:9094").map_err(|e| Err(e.into()))?;
     let config = AzConfig::new().with_endpoint(Some(endpoint));
     let client = AzClient::new(config);
 
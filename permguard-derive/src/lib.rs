@@ -0,0 +1,427 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derive macros for the Permguard SDK, re-exported by `permguard` with the `derive` feature.
+//!
+//! `AzResource`, `AzSubject` and `CedarEntity` implement `TryFrom<T>` and `TryFrom<&T>` for
+//! `Resource`, `Subject` and `CedarEntity`, not `From`: serializing a field can fail, and a `From`
+//! conversion would have to panic or drop the field. Convert with `Resource::try_from(&value)?`
+//! or `value.try_into()?`.
+//!
+//! Container attributes, all optional:
+//! - `#[az(type = "Document")]`: the type name; defaults to the struct name, or `user` for subjects.
+//! - `#[az(namespace = "Acme::Docs")]`: prefixes resource and entity types, and parent types without `::`.
+//! - `#[az(source = "keycloak")]`: the subject source.
+//!
+//! Field attributes:
+//! - `#[az(id)]`: the ID field; defaults to the field named `id`.
+//! - `#[az(skip)]`: leaves the field out of the properties.
+//! - `#[az(rename = "name")]`: the property name; defaults to the field name.
+//! - `#[az(parent = "Folder")]`: an entity parent with the field value as ID. The field may be
+//!   an `Option` or a `Vec` of IDs. Parent fields are not properties.
+//!
+//! Fields are turned into properties with `serde_json`, so they must implement `Serialize`; a
+//! field that does not is a compile error. The conversions fail with `AzError::InvalidRequest`
+//! when a field cannot be serialized at runtime, e.g. a map with non-string keys.
+//!
+//! `AzAction` is derived for enums of unit variants, with `#[az(namespace = "...")]` and
+//! `#[az(rename_all = "...")]` on the enum and `#[az(rename = "...")]` on variants. Variant names
+//! become `snake_case` action names unless `rename_all` is `camelCase`, `PascalCase`,
+//! `lowercase` or `kebab-case`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, PathArguments, Type};
+
+/// Derives `permguard::az_req::az_action::AzAction` for an enum of unit variants.
+#[proc_macro_derive(AzAction, attributes(az))]
+pub fn derive_az_action(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_action(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derives `TryFrom<T>` and `TryFrom<&T>` for `permguard::az_req::model::Resource`.
+#[proc_macro_derive(AzResource, attributes(az))]
+pub fn derive_az_resource(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput), Kind::Resource)
+}
+
+/// Derives `TryFrom<T>` and `TryFrom<&T>` for `permguard::az_req::model::Subject`.
+#[proc_macro_derive(AzSubject, attributes(az))]
+pub fn derive_az_subject(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput), Kind::Subject)
+}
+
+/// Derives `TryFrom<T>` and `TryFrom<&T>` for `permguard::az_req::model::CedarEntity`.
+#[proc_macro_derive(CedarEntity, attributes(az))]
+pub fn derive_cedar_entity(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput), Kind::Entity)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Resource,
+    Subject,
+    Entity,
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    type_name: Option<String>,
+    namespace: Option<String>,
+    source: Option<String>,
+}
+
+struct FieldInfo {
+    ident: Ident,
+    ty: Type,
+    property: String,
+    id: bool,
+    skip: bool,
+    parent: Option<String>,
+}
+
+fn expand(input: DeriveInput, kind: Kind) -> TokenStream {
+    match try_expand(&input, kind) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn try_expand(input: &DeriveInput, kind: Kind) -> syn::Result<TokenStream2> {
+    let attrs = container_attrs(input)?;
+    let fields = fields(input)?;
+
+    let ids: Vec<&FieldInfo> = fields.iter().filter(|f| f.id).collect();
+    let id_field = match ids.as_slice() {
+        [id] => *id,
+        [] => fields.iter().find(|f| f.ident == "id").ok_or_else(|| {
+            syn::Error::new_spanned(&input.ident, "no ID field: name a field `id` or mark one with `#[az(id)]`")
+        })?,
+        [_, second, ..] => return Err(syn::Error::new_spanned(&second.ident, "only one field can be marked `#[az(id)]`")),
+    };
+
+    let type_name = attrs.type_name.clone().unwrap_or_else(|| match kind {
+        Kind::Subject => "user".to_string(),
+        _ => input.ident.to_string(),
+    });
+    let entity_type = match (&attrs.namespace, kind) {
+        (Some(namespace), Kind::Resource | Kind::Entity) => format!("{}::{}", namespace, type_name),
+        _ => type_name,
+    };
+
+    let id_ident = &id_field.ident;
+    let id = quote! { ::std::string::ToString::to_string(&value.#id_ident) };
+    let properties = properties(&fields, id_ident, &input.ident.to_string());
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (target, body) = match kind {
+        Kind::Resource => (
+            quote! { ::permguard::az_req::model::Resource },
+            quote! {
+                #properties
+                ::std::result::Result::Ok(Self {
+                    r#type: #entity_type.to_string(),
+                    id: #id,
+                    properties: ::std::option::Option::Some(properties),
+                })
+            },
+        ),
+        Kind::Subject => {
+            let source = match &attrs.source {
+                Some(source) => quote! { ::std::option::Option::Some(#source.to_string()) },
+                None => quote! { ::std::option::Option::None },
+            };
+            (
+                quote! { ::permguard::az_req::model::Subject },
+                quote! {
+                    #properties
+                    ::std::result::Result::Ok(Self {
+                        r#type: #entity_type.to_string(),
+                        id: #id,
+                        source: #source,
+                        properties: ::std::option::Option::Some(properties),
+                    })
+                },
+            )
+        }
+        Kind::Entity => {
+            let parents = parents(&fields, attrs.namespace.as_deref());
+            (
+                quote! { ::permguard::az_req::model::CedarEntity },
+                quote! {
+                    #properties
+                    let mut parents = ::std::vec::Vec::new();
+                    #parents
+                    ::std::result::Result::Ok(Self {
+                        uid: ::permguard::az_req::model::EntityUid {
+                            r#type: #entity_type.to_string(),
+                            id: #id,
+                        },
+                        attrs: properties,
+                        parents,
+                        tags: ::std::collections::HashMap::new(),
+                    })
+                },
+            )
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::convert::TryFrom<&#name #ty_generics> for #target #where_clause {
+            type Error = ::permguard::error::AzError;
+
+            fn try_from(value: &#name #ty_generics) -> ::std::result::Result<Self, Self::Error> {
+                #body
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<#name #ty_generics> for #target #where_clause {
+            type Error = ::permguard::error::AzError;
+
+            fn try_from(value: #name #ty_generics) -> ::std::result::Result<Self, Self::Error> {
+                <Self as ::std::convert::TryFrom<&#name #ty_generics>>::try_from(&value)
+            }
+        }
+    })
+}
+
+fn expand_action(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "`AzAction` can only be derived for enums"));
+    };
+
+    let mut namespace = String::new();
+    let mut rename_all = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("az")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("namespace") {
+                namespace = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("rename_all") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                if !["snake_case", "camelCase", "PascalCase", "lowercase", "kebab-case"].contains(&value.value().as_str()) {
+                    return Err(syn::Error::new_spanned(value, "unsupported `rename_all` case"));
+                }
+                rename_all = Some(value.value());
+            } else {
+                return Err(meta.error("expected `namespace` or `rename_all`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(variant, "`AzAction` variants cannot have fields"));
+        }
+        let mut name = rename_case(&variant.ident.to_string(), rename_all.as_deref().unwrap_or("snake_case"));
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("az")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename`"))
+                }
+            })?;
+        }
+        let ident = &variant.ident;
+        arms.push(quote! { Self::#ident => #name, });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name_body = if arms.is_empty() {
+        quote! { match *self {} }
+    } else {
+        quote! { match self { #(#arms)* } }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::permguard::az_req::az_action::AzAction for #name #ty_generics #where_clause {
+            fn namespace(&self) -> &str {
+                #namespace
+            }
+
+            fn name(&self) -> &str {
+                #name_body
+            }
+        }
+    })
+}
+
+/// Converts a `PascalCase` variant name to the requested case.
+fn rename_case(variant: &str, case: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for c in variant.chars() {
+        if c.is_uppercase() || words.is_empty() {
+            words.push(String::new());
+        }
+        words.last_mut().expect("a word was pushed").push(c);
+    }
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    match case {
+        "camelCase" => {
+            let mut name = lower.first().cloned().unwrap_or_default();
+            for word in &words[1.min(words.len())..] {
+                name.push_str(word);
+            }
+            name
+        }
+        "PascalCase" => variant.to_string(),
+        "lowercase" => lower.concat(),
+        "kebab-case" => lower.join("-"),
+        _ => lower.join("_"),
+    }
+}
+
+fn container_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+    let mut attrs = ContainerAttrs::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("az")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type") {
+                attrs.type_name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("namespace") {
+                attrs.namespace = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("source") {
+                attrs.source = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `type`, `namespace` or `source`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn fields(input: &DeriveInput) -> syn::Result<Vec<FieldInfo>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "only structs can be derived"));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(&input.ident, "only structs with named fields can be derived"));
+    };
+
+    let mut fields = Vec::new();
+    for field in &named.named {
+        let ident = field.ident.clone().expect("named field");
+        let mut info = FieldInfo {
+            property: ident.to_string().trim_start_matches("r#").to_string(),
+            ident,
+            ty: field.ty.clone(),
+            id: false,
+            skip: false,
+            parent: None,
+        };
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("az")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    info.id = true;
+                } else if meta.path.is_ident("skip") {
+                    info.skip = true;
+                } else if meta.path.is_ident("rename") {
+                    info.property = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("parent") {
+                    info.parent = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    return Err(meta.error("expected `id`, `skip`, `rename` or `parent`"));
+                }
+                Ok(())
+            })?;
+        }
+        fields.push(info);
+    }
+    Ok(fields)
+}
+
+/// Builds the `properties` map from the fields that are not the ID, skipped or parents.
+///
+/// A field that cannot be serialized returns an `AzError::InvalidRequest` naming it.
+fn properties(fields: &[FieldInfo], id: &Ident, type_name: &str) -> TokenStream2 {
+    let inserts = fields
+        .iter()
+        .filter(|f| &f.ident != id && !f.skip && f.parent.is_none())
+        .map(|f| {
+            let ident = &f.ident;
+            let property = &f.property;
+            let error = format!(
+                "cannot serialize the `{}` field of `{}`: {{}}",
+                ident.to_string().trim_start_matches("r#"),
+                type_name
+            );
+            quote! {
+                properties.insert(
+                    #property.to_string(),
+                    ::permguard::__private::serde_json::to_value(&value.#ident).map_err(|err| {
+                        ::permguard::error::AzError::InvalidRequest(::std::format!(#error, err))
+                    })?,
+                );
+            }
+        });
+
+    quote! {
+        let mut properties: ::std::collections::HashMap<::std::string::String, ::permguard::__private::serde_json::Value> =
+            ::std::collections::HashMap::new();
+        #(#inserts)*
+    }
+}
+
+/// Pushes an `EntityUid` per parent ID, iterating over `Option` and `Vec` fields.
+fn parents(fields: &[FieldInfo], namespace: Option<&str>) -> TokenStream2 {
+    let pushes = fields.iter().filter_map(|f| {
+        let parent = f.parent.as_ref()?;
+        let parent_type = match namespace {
+            Some(namespace) if !parent.contains("::") => format!("{}::{}", namespace, parent),
+            _ => parent.clone(),
+        };
+        let ident = &f.ident;
+        let ids = if is_collection(&f.ty) {
+            quote! { value.#ident.iter() }
+        } else {
+            quote! { ::std::iter::once(&value.#ident) }
+        };
+        Some(quote! {
+            for id in #ids {
+                parents.push(::permguard::az_req::model::EntityUid {
+                    r#type: #parent_type.to_string(),
+                    id: ::std::string::ToString::to_string(id),
+                });
+            }
+        })
+    });
+
+    quote! { #(#pushes)* }
+}
+
+/// Returns `true` for `Option<T>` and `Vec<T>` fields, whose items are parent IDs.
+fn is_collection(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|segment| {
+        (segment.ident == "Option" || segment.ident == "Vec")
+            && matches!(&segment.arguments, PathArguments::AngleBracketed(args)
+                if matches!(args.args.first(), Some(GenericArgument::Type(_))))
+    })
+}
@@ -14,54 +14,405 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use tonic::transport::Channel;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 use tonic::Request;
-use crate::config::AzConfig;
+use crate::balancer::{self, Balancer, Lease};
+use crate::cache::{CacheStats, DecisionCache};
+use crate::circuit::{CircuitBreaker, CircuitState};
+use crate::coalesce::InFlight;
+use crate::config::{failure_default, AzConfig, FailureMode};
+use crate::error::AzError;
+use crate::interceptor::{AzInterceptor, Interceptors, TokenProvider};
 use crate::az_req::*;
+use crate::az_req::az_action::AzAction;
+use crate::az_req::matrix_builder::{AzMatrix, AzMatrixResponse};
 use crate::mapper::MapService;
 
+#[allow(clippy::tabs_in_doc_comments)]
 pub mod policydecisionpoint {
     tonic::include_proto!("policydecisionpoint");
 }
 
+/// Per-call options for [`AzClient::check_auth_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct AzCallOptions {
+    /// Overrides the default deadline from [`AzConfig::timeout`].
+    pub timeout: Option<Duration>,
+}
+
+impl AzCallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the deadline for this call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
 
+/// Client for the Permguard Policy Decision Point.
+///
+/// The client owns one gRPC channel per PDP replica, shared by every call and
+/// by every clone of the client. Calls are balanced over the replicas and fail
+/// over to another one when a replica cannot be reached. The underlying
+/// connections are re-established automatically when a PDP restarts.
+#[derive(Debug, Clone)]
 pub struct AzClient {
     config: AzConfig,
+    balancer: Arc<Balancer>,
+    cache: Option<Arc<DecisionCache>>,
+    in_flight: Option<Arc<InFlight>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    interceptors: Interceptors,
 }
 
 impl AzClient {
+    /// Creates a new client; the channel is opened lazily on the first check.
     pub fn new(config: AzConfig) -> Self {
-        Self { config }
+        Self {
+            cache: config.cache.clone().map(|c| Arc::new(DecisionCache::new(c))),
+            in_flight: config.coalesce_requests.then(|| Arc::new(InFlight::new())),
+            circuit_breaker: config.circuit_breaker.clone().map(|c| Arc::new(CircuitBreaker::new(c))),
+            interceptors: Interceptors::default(),
+            balancer: Arc::new(Balancer::new(&config)),
+            config,
+        }
+    }
+
+    /// Creates a new client and eagerly connects to the PDP.
+    pub async fn connect(config: AzConfig) -> Result<Self, AzError> {
+        let client = Self::new(config);
+        client.balancer.connect(&client.config).await?;
+
+        Ok(client)
+    }
+
+    /// Sets the provider of the bearer token sent with every RPC.
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.interceptors.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Adds an interceptor that edits the metadata of every RPC, after the headers and the token.
+    pub fn with_interceptor(mut self, interceptor: impl AzInterceptor + 'static) -> Self {
+        self.interceptors.interceptors.push(Arc::new(interceptor));
+        self
     }
 
     /// Perform an authorization check via gRPC.
-    pub async fn check_auth(&self, request: Option<model::AzRequest>) -> Result<model::AzResponse, Box<dyn std::error::Error>> {
-        let endpoint = self
-            .config
-            .endpoint
-            .as_ref()
-            .ok_or("Please provide config")?;
+    pub async fn check_auth(&self, request: Option<model::AzRequest>) -> Result<model::AzResponse, AzError> {
+        self.check_auth_with_options(request, AzCallOptions::default()).await
+    }
 
-        let url = format!("{}://{}:{}", endpoint.schema, endpoint.host, endpoint.port);
+    /// Perform an authorization check of a typed action, replacing the action of the request.
+    pub async fn check_action(&self, mut request: model::AzRequest, action: &impl AzAction) -> Result<model::AzResponse, AzError> {
+        request.action = Some(action.into());
+        self.check_auth(Some(request)).await
+    }
 
-        let channel = Channel::from_shared(url.clone())?
-            .connect()
-            .await?;
+    /// Evaluates every (subject, resource, action) combination of the matrix in one request.
+    pub async fn check_matrix(&self, matrix: AzMatrix) -> Result<AzMatrixResponse, AzError> {
+        if matrix.is_empty() {
+            return Err(AzError::InvalidRequest("the matrix has no subject, resource or action".to_string()));
+        }
+        let response = self.check_auth(Some(matrix.request().clone())).await?;
+        Ok(matrix.into_response(response))
+    }
 
-        let mut client = policydecisionpoint::v1pdp_service_client::V1pdpServiceClient::new(channel);
+    /// Perform an authorization check via gRPC with per-call options.
+    pub async fn check_auth_with_options(
+        &self,
+        request: Option<model::AzRequest>,
+        options: AzCallOptions,
+    ) -> Result<model::AzResponse, AzError> {
+        let mut request = request.ok_or_else(|| AzError::InvalidRequest("no AzRequest provided".to_string()))?;
+        self.apply_defaults(&mut request);
+        if !self.config.skip_request_validation {
+            request.validate().map_err(AzError::Validation)?;
+        }
 
-        let grpc_request: policydecisionpoint::AuthorizationCheckRequest = match request {
-            Some(ref req) => MapService::map_az_request(req),
-            None => return Err("Invalid AzRequest".into()),
+        let cache_key = self.cache.as_ref().map(|c| c.key(&request));
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key)
+            && let Some(cached) = cache.get(key)
+        {
+            return Ok(Self::with_request_ids(cached, &request));
+        }
+
+        let timeout = options.timeout.or(self.config.timeout);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let send = async {
+            match &self.in_flight {
+                Some(in_flight) => in_flight
+                    .run(request.fingerprint(), self.send(&request, deadline))
+                    .await
+                    .map(|response| Self::with_request_ids(response, &request)),
+                None => self.send(&request, deadline).await,
+            }
         };
 
-        let response = client
-            .authorization_check(Request::new(grpc_request))
-            .await?
-            .into_inner();
+        let result = match timeout.zip(deadline) {
+            Some((timeout, deadline)) => match tokio::time::timeout_at(deadline, send).await {
+                Ok(Err(AzError::Timeout(_))) | Err(_) => Err(AzError::Timeout(timeout)),
+                Ok(result) => result,
+            },
+            None => send.await,
+        };
+
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => return self.on_failure(&request, err),
+        };
+
+        if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+            cache.insert(key, &request, &response);
+        }
+
+        Ok(response)
+    }
+
+    /// Fills in the configured zone and ledger when the request leaves them unset.
+    fn apply_defaults(&self, request: &mut model::AzRequest) {
+        let model = &mut request.authorization_model;
+        if model.zone_id == 0
+            && let Some(zone_id) = self.config.zone_id
+        {
+            model.zone_id = zone_id;
+        }
+
+        if let Some(ledger_id) = &self.config.ledger_id {
+            let policy_store = model.policy_store.get_or_insert_with(|| model::PolicyStore {
+                kind: "ledger".to_string(),
+                id: String::new(),
+            });
+            if policy_store.id.is_empty() {
+                policy_store.id = ledger_id.clone();
+            }
+        }
+    }
+
+    /// Maps the request, sends it to the PDP and maps the response back.
+    async fn send(&self, request: &model::AzRequest, deadline: Option<Instant>) -> Result<model::AzResponse, AzError> {
+        let permit = match &self.circuit_breaker {
+            Some(circuit_breaker) => Some(circuit_breaker.acquire()?),
+            None => None,
+        };
+
+        let grpc_request = MapService::map_az_request(request);
+        // The deadline is enforced here too, so that a hung PDP is recorded as a failure
+        // instead of dropping the permit when the caller's timeout fires.
+        let result = until(deadline, self.call_with_retry(grpc_request, deadline)).await;
+        if let Some(permit) = permit {
+            permit.record(&result);
+        }
+        let response = result?;
 
         let mapped_response = MapService::map_grpc_response(&response);
+        MapService::verify_response(request, &mapped_response)?;
 
         Ok(mapped_response)
     }
+
+    /// Answers a shared or cached response with the caller's request IDs.
+    ///
+    /// Request IDs are not part of the request fingerprint, so a response
+    /// obtained for an identical request may carry different ones.
+    fn with_request_ids(mut response: model::AzResponse, request: &model::AzRequest) -> model::AzResponse {
+        response.request_id = request.request_id.clone().unwrap_or_default();
+        for (evaluation_response, evaluation) in response.evaluations.iter_mut().zip(request.evaluations.iter().flatten()) {
+            evaluation_response.request_id = evaluation.request_id.clone();
+        }
+        response
+    }
+
+    /// Returns the decision cache counters, if the cache is enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
+
+    /// Removes every cached decision.
+    pub fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_all();
+        }
+    }
+
+    /// Removes the cached decisions involving the subject.
+    pub fn invalidate_cache_for_subject(&self, subject_id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_subject(subject_id);
+        }
+    }
+
+    /// Removes the cached decisions involving the resource.
+    pub fn invalidate_cache_for_resource(&self, resource_type: &str, resource_id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_resource(resource_type, resource_id);
+        }
+    }
+
+    /// Returns the state of the circuit breaker, if it is enabled.
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.circuit_breaker.as_ref().map(|c| c.state())
+    }
+
+    /// Applies the configured [`FailureMode`] to a failed call.
+    fn on_failure(&self, request: &model::AzRequest, err: AzError) -> Result<model::AzResponse, AzError> {
+        if !err.is_unavailable() {
+            return Err(err);
+        }
+
+        match self.config.failure_mode {
+            FailureMode::Propagate => Err(err),
+            FailureMode::FailClosed => Ok(Self::failure_response(request, false, &err)),
+            FailureMode::FailOpen => Ok(Self::failure_response(request, true, &err)),
+        }
+    }
+
+    /// Synthesizes a response for a PDP that could not be reached.
+    fn failure_response(request: &model::AzRequest, decision: bool, err: &AzError) -> model::AzResponse {
+        let request_id = request.request_id.clone().unwrap_or_default();
+        let context = |id: &str| model::ContextResponse {
+            id: id.to_string(),
+            reason_admin: Some(model::ReasonResponse {
+                code: failure_default::REASON_CODE.to_string(),
+                message: err.to_string(),
+            }),
+            reason_user: Some(model::ReasonResponse {
+                code: failure_default::REASON_CODE.to_string(),
+                message: failure_default::USER_MESSAGE.to_string(),
+            }),
+        };
+
+        model::AzResponse {
+            request_id: request_id.clone(),
+            decision,
+            context: Some(context(&request_id)),
+            evaluations: request
+                .evaluations
+                .iter()
+                .flatten()
+                .map(|e| model::EvaluationResponse {
+                    request_id: e.request_id.clone(),
+                    decision,
+                    context: Some(context(&e.request_id)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Sends the request, retrying transient failures according to the retry policy.
+    async fn call_with_retry(
+        &self,
+        grpc_request: policydecisionpoint::AuthorizationCheckRequest,
+        deadline: Option<Instant>,
+    ) -> Result<policydecisionpoint::AuthorizationCheckResponse, AzError> {
+        let Some(policy) = &self.config.retry_policy else {
+            return self.call(grpc_request, deadline).await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self.call(grpc_request.clone(), deadline).await {
+                Ok(response) => return Ok(response),
+                Err(err) if attempt < policy.max_attempts && policy.is_retryable(&err) => {
+                    tokio::time::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) if attempt > 1 => {
+                    return Err(AzError::Retried { attempts: attempt, last: Box::new(err) });
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Sends a single `AuthorizationCheck` RPC, failing over to other replicas when one is unreachable.
+    async fn call(
+        &self,
+        grpc_request: policydecisionpoint::AuthorizationCheckRequest,
+        deadline: Option<Instant>,
+    ) -> Result<policydecisionpoint::AuthorizationCheckResponse, AzError> {
+        let mut tried = Vec::new();
+        let mut last_err = None;
+        while let Some(lease) = self.balancer.pick(&tried) {
+            tried.push(lease.index());
+            // A replica that hangs until the deadline counts as unavailable.
+            let result = until(deadline, self.call_replica(&lease, grpc_request.clone(), deadline)).await;
+            lease.record(&result);
+            match result {
+                Err(err) if Balancer::fails_over(&err) => last_err = Some(err),
+                result => return result,
+            }
+        }
+
+        Err(last_err.unwrap_or_else(balancer::no_endpoint))
+    }
+
+    /// Sends a single `AuthorizationCheck` RPC to the leased replica.
+    async fn call_replica(
+        &self,
+        lease: &Lease<'_>,
+        grpc_request: policydecisionpoint::AuthorizationCheckRequest,
+        deadline: Option<Instant>,
+    ) -> Result<policydecisionpoint::AuthorizationCheckResponse, AzError> {
+        let channel = lease.channel(&self.config).await?;
+        let mut client = policydecisionpoint::v1pdp_service_client::V1pdpServiceClient::new(channel);
+
+        let budget = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        let mut request = Request::new(grpc_request);
+        self.interceptors.apply(&self.config.headers, request.metadata_mut()).await?;
+        if let Some(budget) = budget {
+            request.set_timeout(budget);
+        }
+
+        let response = client
+            .authorization_check(request)
+            .await
+            .map_err(|status| match (status.code(), budget) {
+                // The PDP enforces the propagated deadline and cancels the call.
+                (tonic::Code::DeadlineExceeded | tonic::Code::Cancelled, Some(budget)) => AzError::Timeout(budget),
+                _ => AzError::from(status),
+            })?
+            .into_inner();
+
+        Ok(response)
+    }
+}
+
+/// Runs `call` until the deadline, failing with [`AzError::Timeout`] once it passes.
+async fn until<T>(deadline: Option<Instant>, call: impl Future<Output = Result<T, AzError>>) -> Result<T, AzError> {
+    let Some(deadline) = deadline else {
+        return call.await;
+    };
+
+    let budget = deadline.saturating_duration_since(Instant::now());
+    tokio::time::timeout_at(deadline, call)
+        .await
+        .unwrap_or(Err(AzError::Timeout(budget)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn client_can_be_shared_across_tasks() {
+        assert_send_sync::<AzClient>();
+        assert_send_sync::<AzConfig>();
+        assert_send_sync::<AzError>();
+        #[cfg(feature = "blocking")]
+        assert_send_sync::<crate::blocking::AzClient>();
+
+        let client = AzClient::new(AzConfig::new());
+        assert_send(&client.check_auth(None));
+        assert_send(&client.check_auth_with_options(None, AzCallOptions::new()));
+    }
 }
@@ -15,8 +15,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashMap;
+use serde::Serialize;
 use serde_json::Value;
-use crate::az_req::model::Action;
+use crate::az_req::az_action::AzAction;
+use crate::az_req::builder::to_properties;
+use crate::az_req::model::{Action, EntityUid};
+use crate::error::AzError;
 
 #[derive(Debug, Clone)]
 pub struct ActionBuilder {
@@ -33,6 +37,32 @@ impl ActionBuilder {
         }
     }
 
+    /// Create a new ActionBuilder from an action UID; `Ns::Action::"create"` becomes `Ns::Action::create`.
+    pub fn from_uid(uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        Self::new(format!("{}::{}", uid.r#type, uid.id))
+    }
+
+    /// Create a new ActionBuilder from a `(type, id)` pair or a `Ns::Action::"id"` string.
+    pub fn try_from_uid(uid: impl TryInto<EntityUid, Error = AzError>) -> Result<Self, AzError> {
+        Ok(Self::from_uid(uid.try_into()?))
+    }
+
+    /// Replace the name with the one of an action UID, keeping the properties.
+    pub fn with_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        self.name = format!("{}::{}", uid.r#type, uid.id);
+        self
+    }
+
+    /// Create a new ActionBuilder from a typed action, including its properties.
+    pub fn from_action(action: &impl AzAction) -> Self {
+        Self {
+            name: action.action_name(),
+            properties: action.properties(),
+        }
+    }
+
     /// Add or update a property key/value pair.
     pub fn with_property(mut self, key: impl Into<String>, value: Value) -> Self {
         if self.properties.is_none() {
@@ -46,6 +76,12 @@ impl ActionBuilder {
         self
     }
 
+    /// Add every field of a serializable struct or map as a property.
+    pub fn with_properties_from<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, AzError> {
+        self.properties.get_or_insert_with(HashMap::new).extend(to_properties(value)?);
+        Ok(self)
+    }
+
     /// Finalize and build the Action.
     pub fn build(self) -> Action {
         Action {
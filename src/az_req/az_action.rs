@@ -0,0 +1,62 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use serde_json::Value;
+use crate::az_req::model::{Action, EntityUid};
+
+/// A typed Cedar action, e.g. an enum listing the actions of an application.
+///
+/// With the `derive` feature, `#[derive(AzAction)]` implements it for enums of unit variants.
+pub trait AzAction {
+    /// Namespace of the action, e.g. `PharmaAuthZFlow::Platform`; empty for none.
+    fn namespace(&self) -> &str;
+
+    /// Name of the action, e.g. `create`.
+    fn name(&self) -> &str;
+
+    /// Properties sent with the action.
+    fn properties(&self) -> Option<HashMap<String, Value>> {
+        None
+    }
+
+    /// Returns the full action name, e.g. `PharmaAuthZFlow::Platform::Action::create`.
+    fn action_name(&self) -> String {
+        let uid = self.uid();
+        format!("{}::{}", uid.r#type, uid.id)
+    }
+
+    /// Returns the action UID, e.g. `PharmaAuthZFlow::Platform::Action::"create"`.
+    fn uid(&self) -> EntityUid {
+        let action_type = match self.namespace() {
+            "" => "Action".to_string(),
+            namespace => format!("{}::Action", namespace),
+        };
+        EntityUid {
+            r#type: action_type,
+            id: self.name().to_string(),
+        }
+    }
+}
+
+impl<A: AzAction> From<&A> for Action {
+    fn from(action: &A) -> Self {
+        Action {
+            name: action.action_name(),
+            properties: action.properties(),
+        }
+    }
+}
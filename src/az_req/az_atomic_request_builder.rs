@@ -17,6 +17,7 @@
 use serde_json::Value;
 use std::collections::HashMap;
 use crate::az_req::action_builder::ActionBuilder;
+use crate::az_req::az_action::AzAction;
 use crate::az_req::az_request_builder::AzRequestBuilder;
 use crate::az_req::context_builder::ContextBuilder;
 use crate::az_req::model::*;
@@ -82,6 +83,12 @@ impl AzAtomicRequestBuilder {
         self
     }
 
+    /// Adds a Cedar entity.
+    pub fn with_entity(mut self, entity: CedarEntity) -> Self {
+        self.az_request_builder = self.az_request_builder.with_entity(entity);
+        self
+    }
+
     /// Sets the request ID.
     pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
         self.request_id = Some(request_id.into());
@@ -94,6 +101,24 @@ impl AzAtomicRequestBuilder {
         self
     }
 
+    /// Sets the subject type and ID from an entity UID.
+    pub fn with_subject_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        self.az_subject_builder = self.az_subject_builder.with_uid(uid);
+        self
+    }
+
+    /// Sets the resource type and ID from an entity UID.
+    pub fn with_resource_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        self.az_resource_builder = self.az_resource_builder.with_uid(uid);
+        self
+    }
+
+    /// Sets the action name from an action UID, e.g. `Ns::Action::"view"` becomes `Ns::Action::view`.
+    pub fn with_action_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        self.az_action_builder = self.az_action_builder.with_uid(uid);
+        self
+    }
+
     /// Sets the subject kind.
     pub fn with_subject_type(mut self, kind: impl Into<String>) -> Self {
         self.az_subject_builder = self.az_subject_builder.with_type(kind);
@@ -124,6 +149,12 @@ impl AzAtomicRequestBuilder {
         self
     }
 
+    /// Replaces the action with a typed action, including its properties.
+    pub fn with_action(mut self, action: &impl AzAction) -> Self {
+        self.az_action_builder = ActionBuilder::from_action(action);
+        self
+    }
+
     /// Sets an action property.
     pub fn with_action_property(mut self, key: impl Into<String>, value: Value) -> Self {
         self.az_action_builder = self.az_action_builder.with_property(key, value);
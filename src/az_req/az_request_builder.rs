@@ -16,8 +16,14 @@
 
 use serde_json::Value;
 use std::collections::HashMap;
+use crate::az_req::action_builder::ActionBuilder;
 use crate::az_req::builder::deep_copy;
-use crate::az_req::model::{Action, AzModel, AzRequest, Entities, Evaluation, PolicyStore, Principal, Resource, Subject};
+use crate::az_req::entity_builder::entity_default;
+use crate::az_req::model::{
+    Action, AzModel, AzRequest, CedarEntity, Entities, EntityUid, Evaluation, PolicyStore, Principal, Resource, Subject,
+};
+use crate::az_req::resource_builder::ResourceBuilder;
+use crate::az_req::subject_builder::SubjectBuilder;
 
 /// Builder for constructing an [`AzRequest`] instance.
 #[derive(Debug, Clone)]
@@ -82,6 +88,21 @@ impl AzRequestBuilder {
         self
     }
 
+    /// Sets the subject to the entity with the given UID.
+    pub fn with_subject_uid(self, uid: impl Into<EntityUid>) -> Self {
+        self.with_subject(Some(SubjectBuilder::from_uid(uid).build()))
+    }
+
+    /// Sets the resource to the entity with the given UID.
+    pub fn with_resource_uid(self, uid: impl Into<EntityUid>) -> Self {
+        self.with_resource(Some(ResourceBuilder::from_uid(uid).build()))
+    }
+
+    /// Sets the action from an action UID, e.g. `Ns::Action::"view"` becomes `Ns::Action::view`.
+    pub fn with_action_uid(self, uid: impl Into<EntityUid>) -> Self {
+        self.with_action(Some(ActionBuilder::from_uid(uid).build()))
+    }
+
     /// Sets the context.
     pub fn with_context(mut self, context: Option<HashMap<String, Value>>) -> Self {
         self.az_request.context = context.map(|ctx| deep_copy(Some(&ctx)));
@@ -130,6 +151,19 @@ impl AzRequestBuilder {
         self
     }
 
+    /// Adds a Cedar entity, using the `cedar` schema unless another one is set.
+    pub fn with_entity(mut self, entity: CedarEntity) -> Self {
+        let entities = self.az_request.authorization_model.entities.get_or_insert_with(|| Entities {
+            schema: String::new(),
+            items: Vec::new(),
+        });
+        if entities.schema.is_empty() {
+            entities.schema = entity_default::CEDAR_SCHEMA.to_string();
+        }
+        entities.items.push(Some(entity.into()));
+        self
+    }
+
     /// Adds an evaluation.
     pub fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
         self.az_request.evaluations.get_or_insert_with(Vec::new).push(evaluation);
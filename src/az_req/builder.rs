@@ -1,7 +1,9 @@
 // src/az_req/builder.rs
 
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use crate::error::AzError;
 
 /// Performs a deep copy of a JSON-like `HashMap<String, Value>`.
 /// Returns an empty map if `None` is provided.
@@ -11,3 +13,148 @@ pub fn deep_copy(source: Option<&HashMap<String, Value>>) -> HashMap<String, Val
         None => HashMap::new(),
     }
 }
+
+/// Serializes `value` and returns its top-level fields as a property map.
+/// Fails if `value` does not serialize to a JSON object (e.g. a struct or a map).
+pub fn to_properties<T: Serialize + ?Sized>(value: &T) -> Result<HashMap<String, Value>, AzError> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => Ok(map.into_iter().collect()),
+        Ok(other) => Err(AzError::InvalidRequest(format!(
+            "properties must serialize to a JSON object, got {}",
+            json_kind(&other)
+        ))),
+        Err(err) => Err(AzError::InvalidRequest(format!("cannot serialize properties: {}", err))),
+    }
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+    use crate::az_req::action_builder::ActionBuilder;
+    use crate::az_req::context_builder::ContextBuilder;
+    use crate::az_req::resource_builder::ResourceBuilder;
+    use crate::az_req::subject_builder::SubjectBuilder;
+
+    #[derive(Serialize)]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Employee {
+        department: String,
+        is_manager: bool,
+        address: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        manager_id: Option<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        roles: Vec<String>,
+    }
+
+    fn employee() -> Employee {
+        Employee {
+            department: "sales".to_string(),
+            is_manager: false,
+            address: Address { city: "Rome".to_string(), zip: None },
+            manager_id: None,
+            roles: Vec::new(),
+        }
+    }
+
+    fn message(result: Result<HashMap<String, Value>, AzError>) -> String {
+        match result {
+            Err(AzError::InvalidRequest(message)) => message,
+            other => panic!("expected an invalid request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keeps_nested_fields_and_omits_skipped_ones() {
+        let properties = to_properties(&employee()).unwrap();
+
+        assert_eq!(
+            properties,
+            HashMap::from([
+                ("department".to_string(), json!("sales")),
+                ("isManager".to_string(), json!(false)),
+                ("address".to_string(), json!({ "city": "Rome", "zip": null })),
+            ])
+        );
+
+        let mut manager = employee();
+        manager.manager_id = Some("ben".to_string());
+        manager.roles = vec!["admin".to_string()];
+        let properties = to_properties(&manager).unwrap();
+        assert_eq!(properties["managerId"], json!("ben"));
+        assert_eq!(properties["roles"], json!(["admin"]));
+    }
+
+    #[test]
+    fn rejects_values_that_are_not_objects() {
+        assert_eq!(message(to_properties(&vec![1, 2])), "properties must serialize to a JSON object, got an array");
+        assert_eq!(message(to_properties("sales")), "properties must serialize to a JSON object, got a string");
+        assert_eq!(message(to_properties(&42)), "properties must serialize to a JSON object, got a number");
+        assert_eq!(message(to_properties(&None::<Employee>)), "properties must serialize to a JSON object, got null");
+        assert!(message(to_properties(&HashMap::from([((1, 2), 3)]))).starts_with("cannot serialize properties: "));
+
+        assert!(SubjectBuilder::new("amy").with_properties_from(&[1, 2]).is_err());
+        assert!(ContextBuilder::from_serializable(&true).is_err());
+    }
+
+    #[test]
+    fn merges_with_properties_already_set() {
+        let overrides = HashMap::from([("department", "marketing"), ("team", "emea")]);
+
+        let subject = SubjectBuilder::new("amy")
+            .with_property("department", json!("unknown"))
+            .with_property("level", json!(3))
+            .with_properties_from(&employee())
+            .unwrap()
+            .with_properties_from(&overrides)
+            .unwrap()
+            .build()
+            .properties
+            .unwrap();
+        assert_eq!(subject["department"], json!("marketing"));
+        assert_eq!(subject["team"], json!("emea"));
+        assert_eq!(subject["level"], json!(3));
+        assert_eq!(subject["address"]["city"], json!("Rome"));
+
+        let resource = ResourceBuilder::new("App::Document")
+            .with_property("team", json!("apac"))
+            .with_properties_from(&overrides)
+            .unwrap()
+            .build()
+            .properties
+            .unwrap();
+        assert_eq!(resource.len(), 2);
+        assert_eq!(resource["team"], json!("emea"));
+
+        let action = ActionBuilder::new("App::Action::view").with_properties_from(&overrides).unwrap().build();
+        assert_eq!(action.properties.unwrap()["department"], json!("marketing"));
+
+        let context = ContextBuilder::from_serializable(&employee())
+            .unwrap()
+            .with_property("ip", json!("10.0.0.1"))
+            .with_properties_from(&overrides)
+            .unwrap()
+            .build();
+        assert_eq!(context["department"], json!("marketing"));
+        assert_eq!(context["ip"], json!("10.0.0.1"));
+        assert_eq!(context["isManager"], json!(false));
+    }
+}
@@ -14,8 +14,11 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use crate::az_req::builder::to_properties;
+use crate::error::AzError;
 
 /// Builder for creating a context represented as a `HashMap<String, Value>`.
 #[derive(Debug, Default, Clone)]
@@ -31,12 +34,25 @@ impl ContextBuilder {
         }
     }
 
+    /// Creates a `ContextBuilder` from the fields of a serializable struct or map.
+    pub fn from_serializable<T: Serialize + ?Sized>(value: &T) -> Result<Self, AzError> {
+        Ok(Self {
+            context: to_properties(value)?,
+        })
+    }
+
     /// Adds or updates a property in the context.
     pub fn with_property(mut self, key: impl Into<String>, value: Value) -> Self {
         self.context.insert(key.into(), value);
         self
     }
 
+    /// Adds or updates every field of a serializable struct or map in the context.
+    pub fn with_properties_from<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, AzError> {
+        self.context.extend(to_properties(value)?);
+        Ok(self)
+    }
+
     /// Builds and returns the final context map.
     pub fn build(self) -> HashMap<String, Value> {
         self.context
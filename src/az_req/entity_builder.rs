@@ -0,0 +1,140 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use serde_json::Value;
+use crate::az_req::model::{CedarEntity, EntityUid};
+use crate::error::AzError;
+
+/// Default entity constants.
+pub mod entity_default {
+    pub const CEDAR_SCHEMA: &str = "cedar";
+}
+
+/// Builder for [`CedarEntity`].
+#[derive(Debug, Clone)]
+pub struct EntityBuilder {
+    uid: EntityUid,
+    attrs: HashMap<String, Value>,
+    parents: Vec<EntityUid>,
+    tags: HashMap<String, Value>,
+}
+
+impl EntityBuilder {
+    /// Creates a new `EntityBuilder` for the entity with the given type and ID.
+    ///
+    /// The type is checked with the rest of the request by [`AzRequest::validate`](crate::az_req::model::AzRequest::validate).
+    pub fn new(entity_type: impl Into<String>, id: impl Into<String>) -> Self {
+        Self::from_uid(EntityUid::new(entity_type, id))
+    }
+
+    /// Creates a new `EntityBuilder` for the entity with the given UID.
+    pub fn from_uid(uid: impl Into<EntityUid>) -> Self {
+        Self {
+            uid: uid.into(),
+            attrs: HashMap::new(),
+            parents: Vec::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    /// Creates a new `EntityBuilder` from a `(type, id)` pair or a `Ns::Type::"id"` string.
+    pub fn try_from_uid(uid: impl TryInto<EntityUid, Error = AzError>) -> Result<Self, AzError> {
+        Ok(Self::from_uid(uid.try_into()?))
+    }
+
+    /// Adds an attribute to the entity.
+    pub fn with_attribute(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.attrs.insert(key.into(), value);
+        self
+    }
+
+    /// Adds a parent of the entity.
+    pub fn with_parent(mut self, parent: impl Into<EntityUid>) -> Self {
+        self.parents.push(parent.into());
+        self
+    }
+
+    /// Adds a parent given as a `(type, id)` pair or a `Ns::Type::"id"` string.
+    pub fn try_with_parent(self, parent: impl TryInto<EntityUid, Error = AzError>) -> Result<Self, AzError> {
+        Ok(self.with_parent(parent.try_into()?))
+    }
+
+    /// Adds a tag to the entity.
+    pub fn with_tag(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.tags.insert(key.into(), value);
+        self
+    }
+
+    /// Builds the final [`CedarEntity`] instance.
+    pub fn build(self) -> CedarEntity {
+        CedarEntity {
+            uid: self.uid,
+            attrs: self.attrs,
+            parents: self.parents,
+            tags: self.tags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn entity() -> EntityBuilder {
+        EntityBuilder::new("PharmaAuthZFlow::Platform::User", "amy")
+            .with_attribute("active", json!(true))
+            .with_attribute("department", json!({ "name": "sales" }))
+            .with_parent(EntityUid::new("PharmaAuthZFlow::Platform::Group", "admins"))
+            .with_parent(EntityUid::new("PharmaAuthZFlow::Platform::Group", "staff"))
+    }
+
+    #[test]
+    fn produces_the_cedar_entity_json() {
+        let expected = json!({
+            "uid": { "type": "PharmaAuthZFlow::Platform::User", "id": "amy" },
+            "attrs": { "active": true, "department": { "name": "sales" } },
+            "parents": [
+                { "type": "PharmaAuthZFlow::Platform::Group", "id": "admins" },
+                { "type": "PharmaAuthZFlow::Platform::Group", "id": "staff" },
+            ],
+        });
+
+        let item: HashMap<String, Value> = entity().build().into();
+        assert_eq!(Value::Object(item.into_iter().collect()), expected);
+        assert_eq!(serde_json::to_value(entity().build()).unwrap(), expected);
+    }
+
+    #[test]
+    fn adds_tags_only_when_set() {
+        let expected = json!({
+            "uid": { "type": "App::Document", "id": "d1" },
+            "attrs": {},
+            "parents": [],
+            "tags": { "confidential": true },
+        });
+
+        let entity = EntityBuilder::new("App::Document", "d1").with_tag("confidential", json!(true)).build();
+        let item: HashMap<String, Value> = entity.clone().into();
+        assert_eq!(Value::Object(item.into_iter().collect()), expected);
+        assert_eq!(serde_json::to_value(entity).unwrap(), expected);
+
+        let item: HashMap<String, Value> = EntityBuilder::new("App::Document", "d1").build().into();
+        assert!(!item.contains_key("tags"));
+    }
+}
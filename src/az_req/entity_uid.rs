@@ -0,0 +1,359 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::str::FromStr;
+use serde_json::Value;
+use crate::az_req::model::EntityUid;
+use crate::error::AzError;
+
+/// Identifiers Cedar reserves and that cannot name a type or a namespace.
+const RESERVED_IDENTIFIERS: &[&str] = &["true", "false", "if", "then", "else", "in", "is", "like", "has", "__cedar"];
+
+impl EntityUid {
+    /// Creates a UID from a type, e.g. `PharmaAuthZFlow::Platform::Subscription`, and an ID.
+    ///
+    /// Like [`EntityBuilder::new`](crate::az_req::entity_builder::EntityBuilder::new), it does not
+    /// validate the type: [`AzRequest::validate`](crate::az_req::model::AzRequest::validate) checks
+    /// every entity before the request is sent. Use [`EntityUid::try_new`] to validate it now.
+    pub fn new(entity_type: impl Into<String>, id: impl Into<String>) -> Self {
+        Self { r#type: entity_type.into(), id: id.into() }
+    }
+
+    /// Creates a UID, validating the identifiers of the type path.
+    pub fn try_new(entity_type: impl Into<String>, id: impl Into<String>) -> Result<Self, AzError> {
+        let entity_type = entity_type.into();
+        let id = id.into();
+        validate_type_path(&entity_type).map_err(|reason| AzError::InvalidEntityUid {
+            input: format!("{}::{:?}", entity_type, id),
+            reason,
+        })?;
+
+        Ok(Self { r#type: entity_type, id })
+    }
+
+    /// Parses the Cedar syntax `Ns::Type::"id"`, with the ID as an escaped string literal.
+    pub fn parse(input: &str) -> Result<Self, AzError> {
+        let invalid = |reason: String| AzError::InvalidEntityUid { input: input.to_string(), reason };
+
+        let quote = input
+            .find("::\"")
+            .ok_or_else(|| invalid("expected `Type::\"id\"`".to_string()))?;
+        let entity_type = &input[..quote];
+        validate_type_path(entity_type).map_err(invalid)?;
+        let id = parse_string_literal(&input[quote + 2..]).map_err(invalid)?;
+
+        Ok(Self { r#type: entity_type.to_string(), id })
+    }
+
+    /// Returns the namespace of the type, e.g. `PharmaAuthZFlow::Platform`.
+    pub fn namespace(&self) -> Option<&str> {
+        self.r#type.rsplit_once("::").map(|(namespace, _)| namespace)
+    }
+
+    /// Returns the type name without its namespace, e.g. `Subscription`.
+    pub fn type_name(&self) -> &str {
+        self.r#type.rsplit_once("::").map_or(self.r#type.as_str(), |(_, name)| name)
+    }
+
+    /// Returns the `{"type": ..., "id": ...}` JSON form used in entity `uid` and `parents`.
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({ "type": self.r#type, "id": self.id })
+    }
+}
+
+impl fmt::Display for EntityUid {
+    /// Formats the UID as `Ns::Type::"id"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::\"", self.r#type)?;
+        for c in self.id.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                '\0' => f.write_str("\\0")?,
+                c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+impl FromStr for EntityUid {
+    type Err = AzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for EntityUid {
+    type Error = AzError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for EntityUid {
+    type Error = AzError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl<T: Into<String>, I: Into<String>> TryFrom<(T, I)> for EntityUid {
+    type Error = AzError;
+
+    /// Creates a UID from a `(type, id)` pair, validating the type like [`EntityUid::try_new`].
+    fn try_from((entity_type, id): (T, I)) -> Result<Self, Self::Error> {
+        Self::try_new(entity_type, id)
+    }
+}
+
+impl From<&EntityUid> for EntityUid {
+    fn from(uid: &EntityUid) -> Self {
+        uid.clone()
+    }
+}
+
+impl From<EntityUid> for Value {
+    fn from(uid: EntityUid) -> Self {
+        uid.to_json()
+    }
+}
+
+/// Checks that the path is made of valid, non-reserved Cedar identifiers.
+pub(crate) fn validate_type_path(path: &str) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("the type must not be empty".to_string());
+    }
+    for identifier in path.split("::") {
+        validate_identifier(identifier)?;
+    }
+    Ok(())
+}
+
+fn validate_identifier(identifier: &str) -> Result<(), String> {
+    let mut chars = identifier.chars();
+    let valid = match chars.next() {
+        Some(first) => {
+            (first.is_ascii_alphabetic() || first == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    };
+
+    if !valid {
+        return Err(format!("`{}` is not a valid identifier", identifier));
+    }
+    if RESERVED_IDENTIFIERS.contains(&identifier) {
+        return Err(format!("`{}` is a reserved identifier", identifier));
+    }
+    Ok(())
+}
+
+/// Parses a double-quoted string literal that must span the whole input.
+fn parse_string_literal(literal: &str) -> Result<String, String> {
+    let body = literal
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| "the id must be a double-quoted string".to_string())?;
+
+    let mut id = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('"') => id.push('"'),
+                Some('\'') => id.push('\''),
+                Some('\\') => id.push('\\'),
+                Some('n') => id.push('\n'),
+                Some('r') => id.push('\r'),
+                Some('t') => id.push('\t'),
+                Some('0') => id.push('\0'),
+                Some('u') => id.push(parse_unicode_escape(&mut chars)?),
+                Some(other) => return Err(format!("unknown escape `\\{}`", other)),
+                None => return Err("unterminated escape".to_string()),
+            },
+            '"' => return Err("unescaped `\"` in the id".to_string()),
+            c => id.push(c),
+        }
+    }
+    Ok(id)
+}
+
+/// Parses the `{hex}` part of a `\u{hex}` escape.
+fn parse_unicode_escape(chars: &mut std::str::Chars<'_>) -> Result<char, String> {
+    if chars.next() != Some('{') {
+        return Err("expected `{` after `\\u`".to_string());
+    }
+    let mut hex = String::new();
+    loop {
+        match chars.next() {
+            Some('}') => break,
+            Some(c) => hex.push(c),
+            None => return Err("unterminated unicode escape".to_string()),
+        }
+    }
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .filter(|_| (1..=6).contains(&hex.len()))
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("invalid unicode escape `\\u{{{}}}`", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::az_req::action_builder::ActionBuilder;
+    use crate::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+    use crate::az_req::az_request_builder::AzRequestBuilder;
+    use crate::az_req::entity_builder::EntityBuilder;
+    use crate::az_req::resource_builder::ResourceBuilder;
+    use crate::az_req::subject_builder::SubjectBuilder;
+
+    fn reason(result: Result<EntityUid, AzError>) -> String {
+        match result {
+            Err(AzError::InvalidEntityUid { reason, .. }) => reason,
+            other => panic!("expected an invalid UID, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_types_and_ids() {
+        let uid = EntityUid::parse(r#"PharmaAuthZFlow::Platform::Subscription::"e3a786fd""#).unwrap();
+        assert_eq!(uid, EntityUid::new("PharmaAuthZFlow::Platform::Subscription", "e3a786fd"));
+
+        let uid: EntityUid = r#"User::"a::\"b\"""#.parse().unwrap();
+        assert_eq!(uid, EntityUid::new("User", "a::\"b\""));
+
+        let uid = EntityUid::parse(r#"User::"tab\there \u{1F600} \\ \'""#).unwrap();
+        assert_eq!(uid.id, "tab\there \u{1F600} \\ '");
+    }
+
+    #[test]
+    fn rejects_malformed_uids() {
+        assert_eq!(reason(EntityUid::parse("User")), "expected `Type::\"id\"`");
+        assert_eq!(reason(EntityUid::parse(r#"User::"amy"#)), "the id must be a double-quoted string");
+        assert_eq!(reason(EntityUid::parse(r#"User::"a"b""#)), "unescaped `\"` in the id");
+        assert_eq!(reason(EntityUid::parse(r#"User::"a\qb""#)), "unknown escape `\\q`");
+        assert_eq!(reason(EntityUid::parse(r#"User::"\u{110000}""#)), "invalid unicode escape `\\u{110000}`");
+        assert_eq!(reason(EntityUid::parse(r#"User::"\u0041""#)), "expected `{` after `\\u`");
+        assert_eq!(reason(EntityUid::parse(r#"::"amy""#)), "the type must not be empty");
+    }
+
+    #[test]
+    fn rejects_invalid_type_paths() {
+        for (path, expected) in [
+            ("App::", "`` is not a valid identifier"),
+            ("App::::User", "`` is not a valid identifier"),
+            ("1App::User", "`1App` is not a valid identifier"),
+            ("App::Us-er", "`Us-er` is not a valid identifier"),
+            ("App::User ", "`User ` is not a valid identifier"),
+            ("App::if::User", "`if` is a reserved identifier"),
+            ("__cedar::User", "`__cedar` is a reserved identifier"),
+        ] {
+            assert_eq!(reason(EntityUid::try_new(path, "amy")), expected, "{}", path);
+        }
+        assert!(EntityUid::try_new("_App::User2", "amy").is_ok());
+    }
+
+    #[test]
+    fn splits_namespace_and_type_name() {
+        let uid = EntityUid::new("PharmaAuthZFlow::Platform::Subscription", "e3a786fd");
+        assert_eq!(uid.namespace(), Some("PharmaAuthZFlow::Platform"));
+        assert_eq!(uid.type_name(), "Subscription");
+
+        let uid = EntityUid::new("User", "amy");
+        assert_eq!(uid.namespace(), None);
+        assert_eq!(uid.type_name(), "User");
+    }
+
+    #[test]
+    fn formats_and_parses_back() {
+        for id in ["e3a786fd", "", "a\"b", "back\\slash", "line\nbreak\r\t", "nul\0", "bell\u{7}", "ünï €"] {
+            let uid = EntityUid::new("App::User", id);
+            assert_eq!(EntityUid::parse(&uid.to_string()).unwrap(), uid, "{:?}", id);
+        }
+        assert_eq!(EntityUid::new("App::User", "a\"b\u{7}").to_string(), r#"App::User::"a\"b\u{7}""#);
+        assert_eq!(
+            EntityUid::new("App::User", "amy").to_json(),
+            serde_json::json!({ "type": "App::User", "id": "amy" })
+        );
+    }
+
+    #[test]
+    fn validates_tuples_and_strings() {
+        assert_eq!(EntityUid::try_from(("App::User", "amy")).unwrap(), EntityUid::new("App::User", "amy"));
+        assert_eq!(EntityUid::try_from(r#"App::User::"amy""#.to_string()).unwrap(), EntityUid::new("App::User", "amy"));
+
+        match EntityUid::try_from(("bad type", "amy")) {
+            Err(AzError::InvalidEntityUid { input, reason }) => {
+                assert_eq!(input, r#"bad type::"amy""#);
+                assert_eq!(reason, "`bad type` is not a valid identifier");
+            }
+            other => panic!("expected an invalid UID, got {:?}", other),
+        }
+        assert_eq!(reason(EntityUid::try_from(("App::in", "amy"))), "`in` is a reserved identifier");
+        assert_eq!(reason(EntityUid::try_from((String::new(), "amy"))), "the type must not be empty");
+    }
+
+    #[test]
+    fn builders_accept_uids() {
+        let uid = EntityUid::new("App::Document", "d1");
+
+        let resource = ResourceBuilder::from_uid(&uid).build();
+        assert_eq!((resource.r#type.as_str(), resource.id.as_str()), ("App::Document", "d1"));
+        let subject = SubjectBuilder::from_uid(EntityUid::new("App::User", "amy")).with_source("idp").build();
+        assert_eq!((subject.r#type.as_str(), subject.id.as_str()), ("App::User", "amy"));
+        let action = ActionBuilder::try_from_uid(("App::Action", "view")).unwrap().build();
+        assert_eq!(action.name, "App::Action::view");
+        let entity = EntityBuilder::from_uid(&uid).try_with_parent(r#"App::Folder::"f1""#).unwrap().build();
+        assert_eq!(entity.parents, [EntityUid::new("App::Folder", "f1")]);
+
+        assert!(ResourceBuilder::try_from_uid(("bad type", "d1")).is_err());
+        assert!(SubjectBuilder::try_from_uid("amy").is_err());
+        assert!(EntityBuilder::new("App::Document", "d1").try_with_parent(("App::", "f1")).is_err());
+    }
+
+    #[test]
+    fn request_builders_accept_uids() {
+        let request = AzAtomicRequestBuilder::new(1, "ledger", "amy", "App::Document", "view")
+            .with_subject_property("role", serde_json::json!("admin"))
+            .with_subject_uid(EntityUid::new("App::User", "ben"))
+            .with_resource_uid(EntityUid::new("App::Document", "d1"))
+            .with_action_uid(EntityUid::new("App::Action", "edit"))
+            .build();
+        let subject = request.subject.unwrap();
+        assert_eq!((subject.r#type.as_str(), subject.id.as_str()), ("App::User", "ben"));
+        assert!(subject.properties.unwrap().contains_key("role"));
+        assert_eq!(request.resource.unwrap().id, "d1");
+        assert_eq!(request.action.unwrap().name, "App::Action::edit");
+
+        let request = AzRequestBuilder::new(1, "ledger")
+            .with_subject_uid(EntityUid::new("App::User", "amy"))
+            .with_resource_uid(EntityUid::new("App::Document", "d1"))
+            .with_action_uid(EntityUid::new("App::Action", "view"))
+            .build();
+        assert_eq!(request.subject.unwrap().r#type, "App::User");
+        assert_eq!(request.resource.unwrap().r#type, "App::Document");
+        assert_eq!(request.action.unwrap().name, "App::Action::view");
+    }
+}
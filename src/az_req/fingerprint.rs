@@ -0,0 +1,294 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Write;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use crate::az_req::model::{AzRequest, Evaluation};
+
+/// Options controlling how a request fingerprint is computed.
+#[derive(Debug, Clone, Default)]
+pub struct FingerprintOptions {
+    /// Context keys left out of the fingerprint, e.g. volatile timestamps.
+    pub excluded_context_keys: Vec<String>,
+    /// Includes the request IDs, which are ignored by default.
+    pub include_request_ids: bool,
+}
+
+impl FingerprintOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leaves the context key out of the fingerprint.
+    pub fn with_excluded_context_key(mut self, key: impl Into<String>) -> Self {
+        self.excluded_context_keys.push(key.into());
+        self
+    }
+
+    /// Sets whether request IDs are part of the fingerprint.
+    pub fn with_request_ids(mut self, include: bool) -> Self {
+        self.include_request_ids = include;
+        self
+    }
+}
+
+impl AzRequest {
+    /// Returns a stable SHA-256 digest identifying the request.
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint_with(&FingerprintOptions::default())
+    }
+
+    /// Returns a stable SHA-256 digest identifying the request, using the given options.
+    pub fn fingerprint_with(&self, options: &FingerprintOptions) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+        if let Value::Object(map) = &mut value {
+            canonicalize_request(map, options);
+            if let Some(Value::Array(evaluations)) = map.get_mut("evaluations") {
+                for evaluation in evaluations.iter_mut().filter_map(Value::as_object_mut) {
+                    canonicalize_request(evaluation, options);
+                }
+            }
+            if let Some(Value::Array(items)) = map
+                .get_mut("authorization_model")
+                .and_then(|m| m.get_mut("entities"))
+                .and_then(|e| e.get_mut("items"))
+            {
+                items.sort_by_cached_key(canonical_string);
+            }
+        }
+        digest(&value)
+    }
+}
+
+impl Evaluation {
+    /// Returns a stable SHA-256 digest identifying the evaluation.
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint_with(&FingerprintOptions::default())
+    }
+
+    /// Returns a stable SHA-256 digest identifying the evaluation, using the given options.
+    pub fn fingerprint_with(&self, options: &FingerprintOptions) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+        if let Value::Object(map) = &mut value {
+            canonicalize_request(map, options);
+        }
+        digest(&value)
+    }
+}
+
+/// Normalizes the fields shared by requests and evaluations.
+///
+/// Missing property maps are treated as empty ones, since both are sent
+/// to the PDP as an empty `Struct`.
+fn canonicalize_request(map: &mut Map<String, Value>, options: &FingerprintOptions) {
+    if !options.include_request_ids {
+        map.remove("request_id");
+    }
+
+    let context = map.entry("context").or_insert(Value::Null);
+    if context.is_null() {
+        *context = Value::Object(Map::new());
+    }
+    if let Value::Object(ctx) = context {
+        for key in &options.excluded_context_keys {
+            ctx.remove(key);
+        }
+    }
+
+    for field in ["subject", "resource", "action"] {
+        if let Some(Value::Object(entity)) = map.get_mut(field) {
+            let properties = entity.entry("properties").or_insert(Value::Null);
+            if properties.is_null() {
+                *properties = Value::Object(Map::new());
+            }
+        }
+    }
+}
+
+fn digest(value: &Value) -> String {
+    let hash = Sha256::digest(canonical_string(value).as_bytes());
+    hash.iter().fold(String::with_capacity(64), |mut out, b| {
+        let _ = write!(out, "{:02x}", b);
+        out
+    })
+}
+
+/// Serializes a JSON value with sorted keys and normalized numbers.
+fn canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(n) => match n.as_f64() {
+            // The PDP receives every number as a double, so 1 and 1.0 are the same value.
+            Some(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => {
+                let _ = write!(out, "{}", f as i64);
+            }
+            Some(f) => {
+                let _ = write!(out, "{}", f);
+            }
+            None => out.push_str(&n.to_string()),
+        },
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use serde_json::json;
+    use super::*;
+    use crate::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+    use crate::az_req::az_request_builder::AzRequestBuilder;
+    use crate::az_req::entity_builder::EntityBuilder;
+    use crate::az_req::evaluation_builder::EvaluationBuilder;
+    use crate::az_req::model::EntityUid;
+    use crate::az_req::subject_builder::SubjectBuilder;
+
+    fn request() -> AzAtomicRequestBuilder {
+        AzAtomicRequestBuilder::new(1, "ledger", "amy", "App::Document", "App::Action::view").with_resource_id("d1")
+    }
+
+    fn evaluation(context: HashMap<String, Value>) -> AzRequest {
+        let evaluation = EvaluationBuilder::new(Some(SubjectBuilder::new("amy").build()), None, None)
+            .with_context(Some(context))
+            .build();
+        AzRequestBuilder::new(1, "ledger").with_evaluation(evaluation).build()
+    }
+
+    #[test]
+    fn ignores_the_insertion_order_of_properties() {
+        let keys: Vec<String> = (0..32).map(|i| format!("key{}", i)).collect();
+        let forward = keys
+            .iter()
+            .fold(request(), |r, k| r.with_subject_property(k.clone(), json!(k)).with_context_property(k.clone(), json!(1)));
+        let backward = keys
+            .iter()
+            .rev()
+            .fold(request(), |r, k| r.with_subject_property(k.clone(), json!(k)).with_context_property(k.clone(), json!(1)));
+
+        assert_eq!(forward.build().fingerprint(), backward.build().fingerprint());
+    }
+
+    #[test]
+    fn treats_integers_and_equal_floats_alike() {
+        let integer = request().with_context_property("amount", json!(1)).build();
+        let float = request().with_context_property("amount", json!(1.0)).build();
+        let other = request().with_context_property("amount", json!(1.5)).build();
+
+        assert_eq!(integer.fingerprint(), float.fingerprint());
+        assert_ne!(integer.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn ignores_the_order_of_entities() {
+        let amy = EntityBuilder::new("App::User", "amy").with_parent(EntityUid::new("App::Group", "admins")).build();
+        let admins = EntityBuilder::new("App::Group", "admins").build();
+
+        let first = request().with_entity(amy.clone()).with_entity(admins.clone()).build();
+        let second = request().with_entity(admins).with_entity(amy).build();
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+    }
+
+    #[test]
+    fn ignores_request_ids_unless_included() {
+        let first = request().with_request_id("1").build();
+        let second = request().with_request_id("2").build();
+        let options = FingerprintOptions::new().with_request_ids(true);
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert_ne!(first.fingerprint_with(&options), second.fingerprint_with(&options));
+    }
+
+    #[test]
+    fn excludes_context_keys_of_the_request() {
+        let first = request().with_context_property("now", json!("10:00")).build();
+        let second = request().with_context_property("now", json!("10:01")).build();
+        let options = FingerprintOptions::new().with_excluded_context_key("now");
+
+        assert_ne!(first.fingerprint(), second.fingerprint());
+        assert_eq!(first.fingerprint_with(&options), second.fingerprint_with(&options));
+    }
+
+    #[test]
+    fn excludes_context_keys_of_evaluations() {
+        let first = evaluation(HashMap::from([("now".to_string(), json!("10:00")), ("ip".to_string(), json!("a"))]));
+        let second = evaluation(HashMap::from([("now".to_string(), json!("10:01")), ("ip".to_string(), json!("a"))]));
+        let third = evaluation(HashMap::from([("now".to_string(), json!("10:01")), ("ip".to_string(), json!("b"))]));
+        let options = FingerprintOptions::new().with_excluded_context_key("now");
+
+        assert_ne!(first.fingerprint(), second.fingerprint());
+        assert_eq!(first.fingerprint_with(&options), second.fingerprint_with(&options));
+        assert_ne!(first.fingerprint_with(&options), third.fingerprint_with(&options));
+
+        let first_evaluation = |r: &AzRequest| r.evaluations.clone().unwrap().remove(0);
+        assert_eq!(
+            first_evaluation(&first).fingerprint_with(&options),
+            first_evaluation(&second).fingerprint_with(&options)
+        );
+    }
+
+    #[test]
+    fn treats_missing_and_empty_properties_alike() {
+        let mut missing = request().build();
+        missing.subject.as_mut().unwrap().properties = None;
+        missing.context = None;
+        let mut empty = request().build();
+        empty.subject.as_mut().unwrap().properties = Some(HashMap::new());
+        empty.context = Some(HashMap::new());
+
+        assert_eq!(missing.fingerprint(), empty.fingerprint());
+    }
+
+    #[test]
+    fn distinguishes_different_requests() {
+        let amy = request().build();
+        let bob = AzAtomicRequestBuilder::new(1, "ledger", "bob", "App::Document", "App::Action::view")
+            .with_resource_id("d1")
+            .build();
+
+        assert_ne!(amy.fingerprint(), bob.fingerprint());
+    }
+}
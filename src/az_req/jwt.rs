@@ -0,0 +1,267 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+use crate::az_req::principal_builder::PrincipalBuilder;
+use crate::az_req::subject_builder::SubjectBuilder;
+use crate::error::AzError;
+
+/// Which JWT claims populate the principal and the subject.
+///
+/// Claim names may be dotted paths into nested claims, e.g. `realm_access.roles`.
+#[derive(Debug, Clone)]
+pub struct ClaimMapping {
+    /// Claim holding the principal and subject ID.
+    pub id_claim: String,
+    /// Claim holding the principal and subject source.
+    pub source_claim: Option<String>,
+    /// Claims copied into the subject properties, as `(claim, property)` pairs.
+    pub subject_properties: Vec<(String, String)>,
+}
+
+impl Default for ClaimMapping {
+    fn default() -> Self {
+        Self {
+            id_claim: "sub".to_string(),
+            source_claim: Some("iss".to_string()),
+            subject_properties: Vec::new(),
+        }
+    }
+}
+
+impl ClaimMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the claim holding the principal and subject ID.
+    pub fn with_id_claim(mut self, claim: impl Into<String>) -> Self {
+        self.id_claim = claim.into();
+        self
+    }
+
+    /// Sets the claim holding the principal and subject source.
+    pub fn with_source_claim(mut self, claim: Option<String>) -> Self {
+        self.source_claim = claim;
+        self
+    }
+
+    /// Copies a claim into the subject property with the given name.
+    pub fn with_subject_property(mut self, claim: impl Into<String>, property: impl Into<String>) -> Self {
+        self.subject_properties.push((claim.into(), property.into()));
+        self
+    }
+}
+
+/// Decodes JWTs, optionally verifying their signature against a JWKS.
+#[derive(Clone)]
+pub struct JwtDecoder {
+    jwks: Option<JwkSet>,
+    issuers: Vec<String>,
+    audiences: Vec<String>,
+    mapping: ClaimMapping,
+}
+
+impl fmt::Debug for JwtDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtDecoder")
+            .field("jwks_keys", &self.jwks.as_ref().map(|jwks| jwks.keys.len()))
+            .field("issuers", &self.issuers)
+            .field("audiences", &self.audiences)
+            .field("mapping", &self.mapping)
+            .finish()
+    }
+}
+
+impl JwtDecoder {
+    /// Creates a decoder that does not verify signatures.
+    ///
+    /// Only use it for tokens already verified upstream, e.g. by a gateway.
+    pub fn unverified() -> Self {
+        Self {
+            jwks: None,
+            issuers: Vec::new(),
+            audiences: Vec::new(),
+            mapping: ClaimMapping::default(),
+        }
+    }
+
+    /// Creates a decoder that verifies signatures against a JWKS document.
+    pub fn from_jwks_json(jwks: &str) -> Result<Self, AzError> {
+        let jwks: JwkSet = serde_json::from_str(jwks).map_err(|e| AzError::Config(format!("invalid JWKS: {}", e)))?;
+        Ok(Self { jwks: Some(jwks), ..Self::unverified() })
+    }
+
+    /// Creates a decoder that verifies signatures against a local JWKS file.
+    pub fn from_jwks_file(path: impl AsRef<Path>) -> Result<Self, AzError> {
+        let path = path.as_ref();
+        let jwks = std::fs::read_to_string(path)
+            .map_err(|e| AzError::Config(format!("cannot read JWKS file `{}`: {}", path.display(), e)))?;
+        Self::from_jwks_json(&jwks)
+    }
+
+    /// Accepts tokens issued by `issuer`; by default any issuer is accepted.
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuers.push(issuer.into());
+        self
+    }
+
+    /// Accepts tokens for `audience`; by default the audience is not checked.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Sets the mapping from claims to the principal and the subject.
+    pub fn with_mapping(mut self, mapping: ClaimMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Decodes the token and validates its signature, expiry, issuer and audience.
+    pub fn decode(&self, token: &str) -> Result<JwtClaims, AzError> {
+        let header = jsonwebtoken::decode_header(token).map_err(invalid_token)?;
+
+        let (key, mut validation) = match &self.jwks {
+            Some(jwks) => {
+                if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+                    return Err(AzError::InvalidToken(format!("algorithm {:?} cannot be verified with a JWKS", header.alg)));
+                }
+                let jwk = match &header.kid {
+                    Some(kid) => jwks.find(kid),
+                    None if jwks.keys.len() == 1 => jwks.keys.first(),
+                    None => None,
+                }
+                .ok_or_else(|| AzError::InvalidToken("no matching key in the JWKS".to_string()))?;
+                // The token picks its own algorithm, so it must be the one the key is meant for.
+                if let Some(key_algorithm) = jwk.common.key_algorithm
+                    && Algorithm::from_str(&key_algorithm.to_string()).ok() != Some(header.alg)
+                {
+                    return Err(AzError::InvalidToken(format!(
+                        "algorithm {:?} does not match the key algorithm {}",
+                        header.alg, key_algorithm
+                    )));
+                }
+                (DecodingKey::from_jwk(jwk).map_err(invalid_token)?, Validation::new(header.alg))
+            }
+            None => {
+                let mut validation = Validation::new(header.alg);
+                validation.insecure_disable_signature_validation();
+                (DecodingKey::from_secret(&[]), validation)
+            }
+        };
+
+        if !self.issuers.is_empty() {
+            validation.set_issuer(&self.issuers);
+        }
+        if self.audiences.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.audiences);
+        }
+
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(token, &key, &validation)
+            .map_err(invalid_token)?
+            .claims;
+
+        Ok(JwtClaims {
+            token: token.to_string(),
+            claims,
+            mapping: self.mapping.clone(),
+        })
+    }
+}
+
+/// The claims of a decoded JWT.
+#[derive(Clone)]
+pub struct JwtClaims {
+    token: String,
+    claims: Map<String, Value>,
+    mapping: ClaimMapping,
+}
+
+impl fmt::Debug for JwtClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtClaims")
+            .field("token", &"<redacted>")
+            .field("claims", &self.claims)
+            .finish()
+    }
+}
+
+impl JwtClaims {
+    /// Returns every claim of the token.
+    pub fn claims(&self) -> &Map<String, Value> {
+        &self.claims
+    }
+
+    /// Returns a claim by name or by dotted path, e.g. `realm_access.roles`.
+    pub fn claim(&self, path: &str) -> Option<&Value> {
+        if let Some(value) = self.claims.get(path) {
+            return Some(value);
+        }
+        let mut parts = path.split('.');
+        let first = self.claims.get(parts.next()?)?;
+        parts.try_fold(first, |value, part| value.get(part))
+    }
+
+    /// Returns a principal builder with the mapped ID and source, carrying the token as identity token.
+    pub fn principal(&self) -> Result<PrincipalBuilder, AzError> {
+        let mut principal = PrincipalBuilder::new(self.string_claim(&self.mapping.id_claim)?);
+        if let Some(source) = self.source()? {
+            principal = principal.with_source(source);
+        }
+        Ok(principal.with_identity_token(self.token.clone()))
+    }
+
+    /// Returns a subject builder with the mapped ID, source and properties.
+    pub fn subject(&self) -> Result<SubjectBuilder, AzError> {
+        let mut subject = SubjectBuilder::new(self.string_claim(&self.mapping.id_claim)?);
+        if let Some(source) = self.source()? {
+            subject = subject.with_source(source);
+        }
+        for (claim, property) in &self.mapping.subject_properties {
+            if let Some(value) = self.claim(claim) {
+                subject = subject.with_property(property.clone(), value.clone());
+            }
+        }
+        Ok(subject)
+    }
+
+    fn source(&self) -> Result<Option<String>, AzError> {
+        match &self.mapping.source_claim {
+            Some(claim) if self.claim(claim).is_some() => self.string_claim(claim).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn string_claim(&self, path: &str) -> Result<String, AzError> {
+        match self.claim(path) {
+            Some(Value::String(value)) => Ok(value.clone()),
+            Some(_) => Err(AzError::InvalidToken(format!("claim `{}` is not a string", path))),
+            None => Err(AzError::InvalidToken(format!("claim `{}` is missing", path))),
+        }
+    }
+}
+
+fn invalid_token(err: jsonwebtoken::errors::Error) -> AzError {
+    AzError::InvalidToken(err.to_string())
+}
@@ -0,0 +1,360 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::az_req::az_request_builder::AzRequestBuilder;
+use crate::az_req::model::{Action, AzRequest, AzResponse, CedarEntity, Evaluation, EvaluationResponse, Principal, Resource, Subject};
+
+/// Builder expanding lists of subjects, resources and actions into one evaluation per combination.
+///
+/// Each evaluation gets a generated request ID, `<subject>:<resource>:<action>` with the
+/// positions in the lists, prefixed by the request ID when one is set.
+#[derive(Debug, Clone)]
+pub struct MatrixBuilder {
+    request_id: Option<String>,
+    subjects: Vec<Subject>,
+    resources: Vec<Resource>,
+    actions: Vec<Action>,
+    az_request_builder: AzRequestBuilder,
+}
+
+impl MatrixBuilder {
+    /// Creates a new [`MatrixBuilder`] with `zone_id` and `ledger_id`.
+    pub fn new(zone_id: i64, ledger_id: impl Into<String>) -> Self {
+        Self {
+            request_id: None,
+            subjects: Vec::new(),
+            resources: Vec::new(),
+            actions: Vec::new(),
+            az_request_builder: AzRequestBuilder::new(zone_id, ledger_id),
+        }
+    }
+
+    /// Sets the request ID, also used as prefix of the evaluation request IDs.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Sets the principal.
+    pub fn with_principal(mut self, principal: Principal) -> Self {
+        self.az_request_builder = self.az_request_builder.with_principal(Some(principal));
+        self
+    }
+
+    /// Sets the context shared by every evaluation.
+    pub fn with_context(mut self, context: Option<HashMap<String, Value>>) -> Self {
+        self.az_request_builder = self.az_request_builder.with_context(context);
+        self
+    }
+
+    /// Adds a Cedar entity.
+    pub fn with_entity(mut self, entity: CedarEntity) -> Self {
+        self.az_request_builder = self.az_request_builder.with_entity(entity);
+        self
+    }
+
+    /// Adds a subject.
+    pub fn with_subject(mut self, subject: impl Into<Subject>) -> Self {
+        self.subjects.push(subject.into());
+        self
+    }
+
+    /// Adds several subjects.
+    pub fn with_subjects<S: Into<Subject>>(mut self, subjects: impl IntoIterator<Item = S>) -> Self {
+        self.subjects.extend(subjects.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds a resource.
+    pub fn with_resource(mut self, resource: impl Into<Resource>) -> Self {
+        self.resources.push(resource.into());
+        self
+    }
+
+    /// Adds several resources.
+    pub fn with_resources<R: Into<Resource>>(mut self, resources: impl IntoIterator<Item = R>) -> Self {
+        self.resources.extend(resources.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds an action, either an [`Action`] or a reference to a typed action.
+    pub fn with_action(mut self, action: impl Into<Action>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    /// Adds several actions.
+    pub fn with_actions<A: Into<Action>>(mut self, actions: impl IntoIterator<Item = A>) -> Self {
+        self.actions.extend(actions.into_iter().map(Into::into));
+        self
+    }
+
+    /// Builds the [`AzMatrix`], with subjects outermost and actions innermost.
+    pub fn build(self) -> AzMatrix {
+        let mut cells = Vec::new();
+        let mut request_builder = self.az_request_builder.with_request_id(self.request_id.clone());
+        for (s, subject) in self.subjects.iter().enumerate() {
+            for (r, resource) in self.resources.iter().enumerate() {
+                for (a, action) in self.actions.iter().enumerate() {
+                    let request_id = match &self.request_id {
+                        Some(prefix) => format!("{}:{}:{}:{}", prefix, s, r, a),
+                        None => format!("{}:{}:{}", s, r, a),
+                    };
+                    request_builder = request_builder.with_evaluation(Evaluation {
+                        request_id,
+                        subject: Some(subject.clone()),
+                        resource: Some(resource.clone()),
+                        action: Some(action.clone()),
+                        context: None,
+                    });
+                    cells.push((s, r, a));
+                }
+            }
+        }
+
+        AzMatrix {
+            request: request_builder.build(),
+            subjects: self.subjects,
+            resources: self.resources,
+            actions: self.actions,
+            cells,
+        }
+    }
+}
+
+/// A request with one evaluation per (subject, resource, action) combination.
+#[derive(Debug, Clone)]
+pub struct AzMatrix {
+    request: AzRequest,
+    subjects: Vec<Subject>,
+    resources: Vec<Resource>,
+    actions: Vec<Action>,
+    cells: Vec<(usize, usize, usize)>,
+}
+
+impl AzMatrix {
+    /// Returns the request to send to the PDP.
+    pub fn request(&self) -> &AzRequest {
+        &self.request
+    }
+
+    /// Returns `true` when a list is empty, so there is nothing to evaluate.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the number of evaluations.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Indexes the evaluation responses by (subject, resource, action).
+    ///
+    /// Evaluations are matched by request ID, falling back to their position.
+    pub fn into_response(self, response: AzResponse) -> AzMatrixResponse {
+        let positions: HashMap<&str, usize> = self
+            .request
+            .evaluations
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, evaluation)| (evaluation.request_id.as_str(), index))
+            .collect();
+
+        let mut evaluations: Vec<Option<EvaluationResponse>> = vec![None; self.cells.len()];
+        for (index, evaluation) in response.evaluations.iter().enumerate() {
+            let index = positions.get(evaluation.request_id.as_str()).copied().unwrap_or(index);
+            if let Some(slot) = evaluations.get_mut(index)
+                && slot.is_none()
+            {
+                *slot = Some(evaluation.clone());
+            }
+        }
+
+        AzMatrixResponse {
+            response,
+            subjects: self.subjects,
+            resources: self.resources,
+            actions: self.actions,
+            cells: self.cells,
+            evaluations,
+        }
+    }
+}
+
+/// The decisions of an [`AzMatrix`], indexed by (subject, resource, action).
+#[derive(Debug, Clone)]
+pub struct AzMatrixResponse {
+    response: AzResponse,
+    subjects: Vec<Subject>,
+    resources: Vec<Resource>,
+    actions: Vec<Action>,
+    cells: Vec<(usize, usize, usize)>,
+    evaluations: Vec<Option<EvaluationResponse>>,
+}
+
+impl AzMatrixResponse {
+    /// Returns the raw response of the PDP.
+    pub fn response(&self) -> &AzResponse {
+        &self.response
+    }
+
+    /// Returns the evaluation response for the positions in the subject, resource and action lists.
+    pub fn evaluation(&self, subject: usize, resource: usize, action: usize) -> Option<&EvaluationResponse> {
+        if subject >= self.subjects.len() || resource >= self.resources.len() || action >= self.actions.len() {
+            return None;
+        }
+        let index = (subject * self.resources.len() + resource) * self.actions.len() + action;
+        self.evaluations.get(index)?.as_ref()
+    }
+
+    /// Returns the decision for the positions in the subject, resource and action lists.
+    pub fn decision(&self, subject: usize, resource: usize, action: usize) -> Option<bool> {
+        self.evaluation(subject, resource, action).map(|e| e.decision)
+    }
+
+    /// Returns the decision for a subject ID, a resource type and ID, and an action name.
+    pub fn decision_for(&self, subject_id: &str, resource_type: &str, resource_id: &str, action_name: &str) -> Option<bool> {
+        let subject = self.subjects.iter().position(|s| s.id == subject_id)?;
+        let resource = self
+            .resources
+            .iter()
+            .position(|r| r.r#type == resource_type && r.id == resource_id)?;
+        let action = self.actions.iter().position(|a| a.name == action_name)?;
+        self.decision(subject, resource, action)
+    }
+
+    /// Returns the combinations the PDP allowed.
+    pub fn allowed(&self) -> impl Iterator<Item = (&Subject, &Resource, &Action)> {
+        self.cells
+            .iter()
+            .zip(&self.evaluations)
+            .filter(|(_, evaluation)| evaluation.as_ref().is_some_and(|e| e.decision))
+            .map(|(&(s, r, a), _)| (&self.subjects[s], &self.resources[r], &self.actions[a]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::az_req::action_builder::ActionBuilder;
+    use crate::az_req::resource_builder::ResourceBuilder;
+    use crate::az_req::subject_builder::SubjectBuilder;
+
+    fn builder() -> MatrixBuilder {
+        MatrixBuilder::new(1, "ledger")
+            .with_subjects(["amy", "ben"].map(|id| SubjectBuilder::new(id).build()))
+            .with_resources(["d1", "d2", "d3"].map(|id| ResourceBuilder::new("App::Document").with_id(id).build()))
+            .with_actions(["App::Action::view", "App::Action::edit"].map(|name| ActionBuilder::new(name).build()))
+    }
+
+    fn evaluation(request_id: &str, decision: bool) -> EvaluationResponse {
+        EvaluationResponse { request_id: request_id.to_string(), decision, context: None }
+    }
+
+    fn response(evaluations: Vec<EvaluationResponse>) -> AzResponse {
+        AzResponse { request_id: String::new(), decision: false, context: None, evaluations }
+    }
+
+    #[test]
+    fn expands_subjects_outermost_and_actions_innermost() {
+        let matrix = builder().with_request_id("req").build();
+        assert_eq!(matrix.len(), 12);
+        assert_eq!(matrix.request().request_id.as_deref(), Some("req"));
+
+        let cells: Vec<(String, &str, &str, &str)> = matrix
+            .request()
+            .evaluations
+            .iter()
+            .flatten()
+            .map(|e| {
+                (
+                    e.request_id.clone(),
+                    e.subject.as_ref().unwrap().id.as_str(),
+                    e.resource.as_ref().unwrap().id.as_str(),
+                    e.action.as_ref().unwrap().name.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(cells[0], ("req:0:0:0".to_string(), "amy", "d1", "App::Action::view"));
+        assert_eq!(cells[1], ("req:0:0:1".to_string(), "amy", "d1", "App::Action::edit"));
+        assert_eq!(cells[2], ("req:0:1:0".to_string(), "amy", "d2", "App::Action::view"));
+        assert_eq!(cells[6], ("req:1:0:0".to_string(), "ben", "d1", "App::Action::view"));
+        assert_eq!(cells[11], ("req:1:2:1".to_string(), "ben", "d3", "App::Action::edit"));
+
+        assert_eq!(builder().build().request().evaluations.as_ref().unwrap()[5].request_id, "0:2:1");
+    }
+
+    #[test]
+    fn has_nothing_to_evaluate_when_a_list_is_empty() {
+        let matrix = MatrixBuilder::new(1, "ledger")
+            .with_subject(SubjectBuilder::new("amy").build())
+            .with_action(ActionBuilder::new("App::Action::view").build())
+            .build();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.len(), 0);
+        assert!(matrix.request().evaluations.is_none());
+
+        let decisions = matrix.into_response(response(Vec::new()));
+        assert_eq!(decisions.decision(0, 0, 0), None);
+        assert_eq!(decisions.allowed().count(), 0);
+    }
+
+    #[test]
+    fn indexes_decisions_by_request_id() {
+        // Only amy may view d2; the PDP answers in reverse order.
+        let matrix = builder().build();
+        let mut evaluations: Vec<EvaluationResponse> = matrix
+            .request()
+            .evaluations
+            .iter()
+            .flatten()
+            .map(|e| evaluation(&e.request_id, e.request_id == "0:1:0"))
+            .collect();
+        evaluations.reverse();
+        let decisions = matrix.into_response(response(evaluations));
+
+        assert_eq!(decisions.decision(0, 1, 0), Some(true));
+        assert_eq!(decisions.decision(0, 1, 1), Some(false));
+        assert_eq!(decisions.decision(1, 1, 0), Some(false));
+        assert_eq!(decisions.evaluation(1, 2, 1).unwrap().request_id, "1:2:1");
+        assert_eq!(decisions.decision(2, 0, 0), None);
+        assert_eq!(decisions.decision(0, 3, 0), None);
+        assert_eq!(decisions.decision(0, 0, 2), None);
+
+        assert_eq!(decisions.decision_for("amy", "App::Document", "d2", "App::Action::view"), Some(true));
+        assert_eq!(decisions.decision_for("ben", "App::Document", "d2", "App::Action::view"), Some(false));
+        assert_eq!(decisions.decision_for("amy", "App::Folder", "d2", "App::Action::view"), None);
+
+        let allowed: Vec<(&str, &str, &str)> = decisions
+            .allowed()
+            .map(|(s, r, a)| (s.id.as_str(), r.id.as_str(), a.name.as_str()))
+            .collect();
+        assert_eq!(allowed, [("amy", "d2", "App::Action::view")]);
+    }
+
+    #[test]
+    fn falls_back_to_positions_for_unknown_request_ids() {
+        let matrix = builder().build();
+        let evaluations = (0..matrix.len()).map(|i| evaluation("", i == 3)).collect();
+        let decisions = matrix.into_response(response(evaluations));
+
+        assert_eq!(decisions.decision(0, 1, 1), Some(true));
+        assert_eq!(decisions.allowed().count(), 1);
+    }
+}
@@ -1,11 +1,19 @@
 pub mod action_builder;
+pub mod az_action;
 pub mod az_atomic_request_builder;
 pub mod az_request_builder;
 pub mod builder;
 pub mod context_builder;
+pub mod entity_builder;
+pub mod entity_uid;
 
 pub mod model;
 pub mod evaluation_builder;
+pub mod fingerprint;
+#[cfg(feature = "jwt")]
+pub mod jwt;
+pub mod matrix_builder;
 pub mod principal_builder;
 pub mod resource_builder;
-pub mod subject_builder;
\ No newline at end of file
+pub mod subject_builder;
+pub mod validation;
\ No newline at end of file
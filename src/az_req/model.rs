@@ -16,6 +16,7 @@
 
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +31,39 @@ pub struct Entities {
     pub items: Vec<Option<HashMap<String, Value>>>,
 }
 
+/// Unique identifier of a Cedar entity, e.g. `PharmaAuthZFlow::Platform::Subscription::"e3a786fd"`.
+///
+/// `type` holds the full type path, namespace included.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityUid {
+    pub r#type: String,
+    pub id: String,
+}
+
+/// A Cedar entity, serialized in the Cedar entity JSON shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CedarEntity {
+    pub uid: EntityUid,
+    pub attrs: HashMap<String, Value>,
+    pub parents: Vec<EntityUid>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, Value>,
+}
+
+impl From<CedarEntity> for HashMap<String, Value> {
+    /// Converts the entity into an item of [`Entities::items`].
+    fn from(entity: CedarEntity) -> Self {
+        let mut item = HashMap::new();
+        item.insert("uid".to_string(), entity.uid.to_json());
+        item.insert("attrs".to_string(), Value::Object(entity.attrs.into_iter().collect()));
+        item.insert("parents".to_string(), Value::Array(entity.parents.iter().map(EntityUid::to_json).collect()));
+        if !entity.tags.is_empty() {
+            item.insert("tags".to_string(), Value::Object(entity.tags.into_iter().collect()));
+        }
+        item
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Evaluation {
     pub request_id: String,
@@ -58,11 +92,32 @@ pub struct AzRequest {
     pub evaluations: Option<Vec<Evaluation>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Principal {
     pub r#type: String,
     pub id: String,
     pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+}
+
+impl fmt::Debug for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Principal")
+            .field("type", &self.r#type)
+            .field("id", &self.id)
+            .field("source", &self.source)
+            .field("identity_token", &redact(&self.identity_token))
+            .field("access_token", &redact(&self.access_token))
+            .finish()
+    }
+}
+
+/// Hides the value of a token in `Debug` output.
+pub(crate) fn redact(token: &Option<String>) -> Option<&'static str> {
+    token.as_ref().map(|_| "<redacted>")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
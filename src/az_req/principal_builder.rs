@@ -14,7 +14,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::az_req::model::Principal;
+use std::fmt;
+use crate::az_req::model::{redact, Principal};
 
 /// Default kind for principals.
 pub mod principal_default {
@@ -22,11 +23,25 @@ pub mod principal_default {
 }
 
 /// Builder for [`Principal`].
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PrincipalBuilder {
     id: String,
     principal_type: String,
     source: Option<String>,
+    identity_token: Option<String>,
+    access_token: Option<String>,
+}
+
+impl fmt::Debug for PrincipalBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrincipalBuilder")
+            .field("id", &self.id)
+            .field("principal_type", &self.principal_type)
+            .field("source", &self.source)
+            .field("identity_token", &redact(&self.identity_token))
+            .field("access_token", &redact(&self.access_token))
+            .finish()
+    }
 }
 
 impl PrincipalBuilder {
@@ -36,6 +51,8 @@ impl PrincipalBuilder {
             id: id.into(),
             principal_type: principal_default::USER_TYPE.to_string(),
             source: None,
+            identity_token: None,
+            access_token: None,
         }
     }
 
@@ -51,12 +68,26 @@ impl PrincipalBuilder {
         self
     }
 
+    /// Sets the identity token (e.g. an OIDC ID token) of the principal.
+    pub fn with_identity_token(mut self, token: impl Into<String>) -> Self {
+        self.identity_token = Some(token.into());
+        self
+    }
+
+    /// Sets the access token of the principal.
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
     /// Builds and returns the final [`Principal`] instance.
     pub fn build(self) -> Principal {
         Principal {
             id: self.id,
             r#type: self.principal_type,
             source: self.source,
+            identity_token: self.identity_token,
+            access_token: self.access_token,
         }
     }
 }
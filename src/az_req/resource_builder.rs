@@ -15,8 +15,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashMap;
+use serde::Serialize;
 use serde_json::Value;
-use crate::az_req::model::Resource;
+use crate::az_req::builder::to_properties;
+use crate::az_req::model::{EntityUid, Resource};
+use crate::error::AzError;
 
 /// Builder for [`Resource`].
 #[derive(Debug, Clone)]
@@ -36,6 +39,24 @@ impl ResourceBuilder {
         }
     }
 
+    /// Creates a new `ResourceBuilder` with the type and ID of the entity UID.
+    pub fn from_uid(uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        Self::new(uid.r#type).with_id(uid.id)
+    }
+
+    /// Creates a new `ResourceBuilder` from a `(type, id)` pair or a `Ns::Type::"id"` string.
+    pub fn try_from_uid(uid: impl TryInto<EntityUid, Error = AzError>) -> Result<Self, AzError> {
+        Ok(Self::from_uid(uid.try_into()?))
+    }
+
+    /// Sets the type and ID of the resource from the entity UID, keeping its properties.
+    pub fn with_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        self.resource_type = uid.r#type;
+        self.with_id(uid.id)
+    }
+
     /// Sets the ID of the resource.
     pub fn with_id(mut self, id: impl Into<String>) -> Self {
         self.id = Some(id.into());
@@ -48,10 +69,16 @@ impl ResourceBuilder {
         self
     }
 
+    /// Adds every field of a serializable struct or map as a resource property.
+    pub fn with_properties_from<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, AzError> {
+        self.properties.extend(to_properties(value)?);
+        Ok(self)
+    }
+
     /// Builds and returns the final [`Resource`] instance.
     pub fn build(self) -> Resource {
         Resource {
-            id: self.id.unwrap_or_else(|| "".to_string()),
+            id: self.id.unwrap_or_default(),
             r#type: self.resource_type,
             properties: Some(self.properties),
         }
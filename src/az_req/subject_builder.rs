@@ -15,8 +15,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashMap;
+use serde::Serialize;
 use serde_json::Value;
-use crate::az_req::model::Subject;
+use crate::az_req::builder::to_properties;
+use crate::az_req::model::{EntityUid, Subject};
+use crate::error::AzError;
 
 /// Default subject constants.
 pub mod subject_default {
@@ -43,6 +46,24 @@ impl SubjectBuilder {
         }
     }
 
+    /// Creates a new `SubjectBuilder` with the type and ID of the entity UID.
+    pub fn from_uid(uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        Self::new(uid.id).with_type(uid.r#type)
+    }
+
+    /// Creates a new `SubjectBuilder` from a `(type, id)` pair or a `Ns::Type::"id"` string.
+    pub fn try_from_uid(uid: impl TryInto<EntityUid, Error = AzError>) -> Result<Self, AzError> {
+        Ok(Self::from_uid(uid.try_into()?))
+    }
+
+    /// Sets the type and ID of the subject from the entity UID, keeping its source and properties.
+    pub fn with_uid(mut self, uid: impl Into<EntityUid>) -> Self {
+        let uid = uid.into();
+        self.id = uid.id;
+        self.with_type(uid.r#type)
+    }
+
     /// Sets the type of the subject.
     pub fn with_type(mut self, kind: impl Into<String>) -> Self {
         self.subject_type = kind.into();
@@ -61,6 +82,12 @@ impl SubjectBuilder {
         self
     }
 
+    /// Adds every field of a serializable struct or map as a subject property.
+    pub fn with_properties_from<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, AzError> {
+        self.properties.extend(to_properties(value)?);
+        Ok(self)
+    }
+
     /// Builds the final [`Subject`] instance.
     pub fn build(self) -> Subject {
         Subject {
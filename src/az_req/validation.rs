@@ -0,0 +1,345 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use serde_json::Value;
+use crate::az_req::entity_uid::validate_type_path;
+use crate::az_req::model::{Action, AzRequest, Entities, Evaluation, Resource, Subject};
+use crate::error::ValidationError;
+
+/// Collects the validation errors of a request.
+#[derive(Default)]
+struct Errors(Vec<ValidationError>);
+
+impl Errors {
+    fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ValidationError { path: path.into(), message: message.into() });
+    }
+
+    fn not_empty(&mut self, path: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(path, "must not be empty");
+        }
+    }
+}
+
+impl AzRequest {
+    /// Checks the request before it is sent to the PDP, returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Errors::default();
+        let model = &self.authorization_model;
+
+        if model.zone_id <= 0 {
+            errors.push("authorization_model.zone_id", "must be a positive zone ID");
+        }
+        match &model.policy_store {
+            Some(policy_store) => {
+                errors.not_empty("authorization_model.policy_store.kind", &policy_store.kind);
+                errors.not_empty("authorization_model.policy_store.id", &policy_store.id);
+            }
+            None => errors.push("authorization_model.policy_store", "is required"),
+        }
+        if let Some(principal) = &model.principal {
+            errors.not_empty("authorization_model.principal.type", &principal.r#type);
+            errors.not_empty("authorization_model.principal.id", &principal.id);
+        }
+        if let Some(entities) = &model.entities {
+            validate_entities(&mut errors, "authorization_model.entities", entities);
+        }
+
+        validate_subject(&mut errors, "subject", &self.subject);
+        validate_resource(&mut errors, "resource", &self.resource);
+        validate_action(&mut errors, "action", &self.action);
+
+        match self.evaluations.as_deref() {
+            None | Some([]) => {
+                for (path, present) in [
+                    ("subject", self.subject.is_some()),
+                    ("resource", self.resource.is_some()),
+                    ("action", self.action.is_some()),
+                ] {
+                    if !present {
+                        errors.push(path, "is required when the request has no evaluations");
+                    }
+                }
+            }
+            Some(evaluations) => {
+                for (index, evaluation) in evaluations.iter().enumerate() {
+                    self.validate_evaluation(&mut errors, &format!("evaluations[{}]", index), evaluation);
+                }
+            }
+        }
+
+        if errors.0.is_empty() { Ok(()) } else { Err(errors.0) }
+    }
+
+    fn validate_evaluation(&self, errors: &mut Errors, path: &str, evaluation: &Evaluation) {
+        validate_subject(errors, &format!("{}.subject", path), &evaluation.subject);
+        validate_resource(errors, &format!("{}.resource", path), &evaluation.resource);
+        validate_action(errors, &format!("{}.action", path), &evaluation.action);
+
+        for (field, present, inherited) in [
+            ("subject", evaluation.subject.is_some(), self.subject.is_some()),
+            ("resource", evaluation.resource.is_some(), self.resource.is_some()),
+            ("action", evaluation.action.is_some(), self.action.is_some()),
+        ] {
+            if !present && !inherited {
+                errors.push(format!("{}.{}", path, field), "is required when the request does not set one");
+            }
+        }
+    }
+}
+
+fn validate_subject(errors: &mut Errors, path: &str, subject: &Option<Subject>) {
+    if let Some(subject) = subject {
+        errors.not_empty(&format!("{}.type", path), &subject.r#type);
+        errors.not_empty(&format!("{}.id", path), &subject.id);
+    }
+}
+
+fn validate_resource(errors: &mut Errors, path: &str, resource: &Option<Resource>) {
+    if let Some(resource) = resource {
+        // The ID may be left empty to address every resource of the type.
+        errors.not_empty(&format!("{}.type", path), &resource.r#type);
+    }
+}
+
+fn validate_action(errors: &mut Errors, path: &str, action: &Option<Action>) {
+    if let Some(action) = action {
+        errors.not_empty(&format!("{}.name", path), &action.name);
+    }
+}
+
+/// Checks that every item has the shape of a Cedar entity: a `uid` with `type` and `id`.
+fn validate_entities(errors: &mut Errors, path: &str, entities: &Entities) {
+    if !entities.items.is_empty() {
+        errors.not_empty(&format!("{}.schema", path), &entities.schema);
+    }
+
+    for (index, item) in entities.items.iter().enumerate() {
+        let path = format!("{}.items[{}]", path, index);
+        let Some(item) = item else {
+            errors.push(path, "must not be null");
+            continue;
+        };
+        validate_entity(errors, &path, item);
+    }
+}
+
+fn validate_entity(errors: &mut Errors, path: &str, item: &HashMap<String, Value>) {
+    match item.get("uid") {
+        Some(uid) => validate_uid(errors, &format!("{}.uid", path), uid),
+        None => errors.push(format!("{}.uid", path), "is required"),
+    }
+
+    if let Some(attrs) = item.get("attrs")
+        && !attrs.is_object()
+    {
+        errors.push(format!("{}.attrs", path), "must be an object");
+    }
+    match item.get("parents") {
+        Some(Value::Array(parents)) => {
+            for (index, parent) in parents.iter().enumerate() {
+                validate_uid(errors, &format!("{}.parents[{}]", path, index), parent);
+            }
+        }
+        Some(_) => errors.push(format!("{}.parents", path), "must be an array"),
+        None => {}
+    }
+}
+
+/// Checks an entity UID: a `type` that is a valid Cedar type path, and an `id`.
+fn validate_uid(errors: &mut Errors, path: &str, uid: &Value) {
+    let Value::Object(uid) = uid else {
+        errors.push(path, "must be an object with `type` and `id`");
+        return;
+    };
+
+    for field in ["type", "id"] {
+        let path = format!("{}.{}", path, field);
+        match uid.get(field) {
+            Some(Value::String(value)) if field == "type" => {
+                if let Err(reason) = validate_type_path(value) {
+                    errors.push(path, reason);
+                }
+            }
+            Some(Value::String(value)) => errors.not_empty(&path, value),
+            Some(_) => errors.push(path, "must be a string"),
+            None => errors.push(path, "is required"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use serde_json::{json, Value};
+    use crate::az_req::action_builder::ActionBuilder;
+    use crate::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+    use crate::az_req::az_request_builder::AzRequestBuilder;
+    use crate::az_req::entity_builder::EntityBuilder;
+    use crate::az_req::evaluation_builder::EvaluationBuilder;
+    use crate::az_req::model::{AzRequest, EntityUid, PolicyStore};
+    use crate::az_req::resource_builder::ResourceBuilder;
+    use crate::az_req::subject_builder::SubjectBuilder;
+
+    fn request() -> AzAtomicRequestBuilder {
+        AzAtomicRequestBuilder::new(1, "ledger", "amy", "App::Document", "App::Action::view").with_resource_id("d1")
+    }
+
+    fn errors(request: &AzRequest) -> Vec<(String, String)> {
+        request.validate().err().unwrap_or_default().into_iter().map(|e| (e.path, e.message)).collect()
+    }
+
+    fn paths(request: &AzRequest) -> Vec<String> {
+        errors(request).into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn with_entity_item(item: Value) -> AzRequest {
+        let Value::Object(item) = item else { unreachable!() };
+        let item: HashMap<String, Value> = item.into_iter().collect();
+        request().with_entities_items("cedar", Some(vec![Some(item)])).build()
+    }
+
+    #[test]
+    fn accepts_a_valid_request() {
+        assert!(request().build().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_a_zero_zone_id() {
+        let request = AzAtomicRequestBuilder::new(0, "ledger", "amy", "App::Document", "App::Action::view").build();
+
+        assert_eq!(
+            errors(&request),
+            [("authorization_model.zone_id".to_string(), "must be a positive zone ID".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_or_missing_policy_store() {
+        let request = AzAtomicRequestBuilder::new(1, " ", "amy", "App::Document", "App::Action::view").build();
+        assert_eq!(paths(&request), ["authorization_model.policy_store.id"]);
+
+        let mut request = request;
+        request.authorization_model.policy_store = Some(PolicyStore { kind: String::new(), id: "ledger".to_string() });
+        assert_eq!(paths(&request), ["authorization_model.policy_store.kind"]);
+
+        request.authorization_model.policy_store = None;
+        assert_eq!(
+            errors(&request),
+            [("authorization_model.policy_store".to_string(), "is required".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_subject_or_action() {
+        let mut request = request().build();
+        request.subject = None;
+        request.action = None;
+
+        assert_eq!(
+            errors(&request),
+            [
+                ("subject".to_string(), "is required when the request has no evaluations".to_string()),
+                ("action".to_string(), "is required when the request has no evaluations".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_an_evaluation_without_subject_when_the_request_has_none() {
+        let evaluation = EvaluationBuilder::new(None, None, None).build();
+        let request = AzRequestBuilder::new(1, "ledger")
+            .with_resource(Some(ResourceBuilder::new("App::Document").build()))
+            .with_action(Some(ActionBuilder::new("App::Action::view").build()))
+            .with_evaluation(evaluation)
+            .build();
+        assert_eq!(paths(&request), ["evaluations[0].subject"]);
+
+        let evaluation = EvaluationBuilder::new(Some(SubjectBuilder::new("").build()), None, None).build();
+        let mut request = request;
+        request.evaluations = Some(vec![evaluation]);
+        assert_eq!(paths(&request), ["evaluations[0].subject.id"]);
+    }
+
+    #[test]
+    fn rejects_a_missing_or_empty_entity_uid() {
+        let request = with_entity_item(json!({ "attrs": {}, "parents": [] }));
+        assert_eq!(
+            errors(&request),
+            [("authorization_model.entities.items[0].uid".to_string(), "is required".to_string())]
+        );
+
+        let request = with_entity_item(json!({ "uid": { "type": "App::User", "id": "" } }));
+        assert_eq!(
+            errors(&request),
+            [("authorization_model.entities.items[0].uid.id".to_string(), "must not be empty".to_string())]
+        );
+
+        let request = with_entity_item(json!({ "uid": "App::User::\"amy\"" }));
+        assert_eq!(paths(&request), ["authorization_model.entities.items[0].uid"]);
+
+        let request = with_entity_item(json!({ "uid": { "id": 7 } }));
+        assert_eq!(
+            errors(&request),
+            [
+                ("authorization_model.entities.items[0].uid.type".to_string(), "is required".to_string()),
+                ("authorization_model.entities.items[0].uid.id".to_string(), "must be a string".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_valid_entity_types() {
+        let entity = EntityBuilder::new("App::User", "amy").with_parent(EntityUid::new("App::Group", "admins"));
+
+        assert!(paths(&request().with_entity(entity.build()).build()).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_entity_and_parent_types() {
+        let entity = EntityBuilder::new("bad type", "amy").with_parent(EntityUid::new("App::if", "admins"));
+
+        assert_eq!(
+            paths(&request().with_entity(entity.build()).build()),
+            ["authorization_model.entities.items[0].uid.type", "authorization_model.entities.items[0].parents[0].type"]
+        );
+    }
+
+    #[test]
+    fn reports_every_violation_at_once() {
+        let mut request = AzAtomicRequestBuilder::new(0, "", "", "", "")
+            .with_entities_items("", Some(vec![None, Some(HashMap::from([("attrs".to_string(), json!([]))]))]))
+            .build();
+        request.action = None;
+
+        assert_eq!(
+            paths(&request),
+            [
+                "authorization_model.zone_id",
+                "authorization_model.policy_store.id",
+                "authorization_model.entities.schema",
+                "authorization_model.entities.items[0]",
+                "authorization_model.entities.items[1].uid",
+                "authorization_model.entities.items[1].attrs",
+                "subject.id",
+                "resource.type",
+                "action",
+            ]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::sync::OnceCell;
+use tonic::transport::Channel;
+use crate::config::{AzConfig, AzEndpoint, BalancingStrategy, EjectionPolicy};
+use crate::error::AzError;
+use crate::transport;
+use crate::util;
+
+/// Spreads calls over the configured PDP replicas and ejects the failing ones.
+#[derive(Debug)]
+pub(crate) struct Balancer {
+    replicas: Vec<Replica>,
+    strategy: BalancingStrategy,
+    ejection: EjectionPolicy,
+    next: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Replica {
+    endpoint: AzEndpoint,
+    channel: OnceCell<Channel>,
+    outstanding: AtomicUsize,
+    health: Mutex<Health>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_failures: u32,
+    ejections: u32,
+    ejected_until: Option<Instant>,
+    probing: bool,
+}
+
+impl Health {
+    fn is_available(&self, now: Instant) -> bool {
+        match self.ejected_until {
+            None => true,
+            Some(until) => until <= now && !self.probing,
+        }
+    }
+}
+
+/// A replica picked for one call; releases its outstanding slot on drop.
+pub(crate) struct Lease<'a> {
+    balancer: &'a Balancer,
+    index: usize,
+    recorded: bool,
+}
+
+impl Balancer {
+    pub(crate) fn new(config: &AzConfig) -> Self {
+        Self {
+            replicas: config
+                .all_endpoints()
+                .map(|endpoint| Replica {
+                    endpoint: endpoint.clone(),
+                    channel: OnceCell::new(),
+                    outstanding: AtomicUsize::new(0),
+                    health: Mutex::new(Health::default()),
+                })
+                .collect(),
+            strategy: config.balancing_strategy,
+            ejection: config.ejection_policy.clone(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Connects to every replica, succeeding when at least one is reachable.
+    pub(crate) async fn connect(&self, config: &AzConfig) -> Result<(), AzError> {
+        let mut first_err = None;
+        for replica in &self.replicas {
+            match transport::connect(config, &replica.endpoint).await {
+                Ok(channel) => {
+                    let _ = replica.channel.set(channel);
+                }
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+
+        match first_err {
+            Some(err) if self.replicas.iter().all(|r| r.channel.get().is_none()) => Err(err),
+            None if self.replicas.is_empty() => Err(no_endpoint()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Picks a replica that is not in `tried`, or `None` once all of them were tried.
+    pub(crate) fn pick(&self, tried: &[usize]) -> Option<Lease<'_>> {
+        let now = Instant::now();
+        let untried: Vec<usize> = (0..self.replicas.len()).filter(|i| !tried.contains(i)).collect();
+        let available: Vec<usize> = untried
+            .iter()
+            .copied()
+            .filter(|&i| util::lock(&self.replicas[i].health).is_available(now))
+            .collect();
+
+        let index = if available.is_empty() {
+            // Every remaining replica is ejected: use the one due for a probe first.
+            *untried
+                .iter()
+                .min_by_key(|&&i| util::lock(&self.replicas[i].health).ejected_until)?
+        } else {
+            self.choose(&available)
+        };
+
+        let replica = &self.replicas[index];
+        let mut health = util::lock(&replica.health);
+        if health.ejected_until.is_some() {
+            health.probing = true;
+        }
+        drop(health);
+        replica.outstanding.fetch_add(1, Ordering::Relaxed);
+
+        Some(Lease { balancer: self, index, recorded: false })
+    }
+
+    fn choose(&self, candidates: &[usize]) -> usize {
+        let offset = self.next.fetch_add(1, Ordering::Relaxed);
+        match self.strategy {
+            BalancingStrategy::RoundRobin => candidates[offset % candidates.len()],
+            BalancingStrategy::Random => {
+                candidates[util::random_u64() as usize % candidates.len()]
+            }
+            BalancingStrategy::LeastOutstanding => {
+                // Rotating the candidates spreads ties instead of favouring the first replica.
+                (0..candidates.len())
+                    .map(|i| candidates[(offset + i) % candidates.len()])
+                    .min_by_key(|&i| self.replicas[i].outstanding.load(Ordering::Relaxed))
+                    .unwrap_or(candidates[0])
+            }
+        }
+    }
+
+    /// Returns `true` when the call may be sent to another replica after `err`.
+    ///
+    /// Only connection-level failures fail over; a timeout has already used the deadline.
+    pub(crate) fn fails_over(err: &AzError) -> bool {
+        match err {
+            AzError::Transport(_) => true,
+            AzError::Status { code, .. } => *code == tonic::Code::Unavailable,
+            _ => false,
+        }
+    }
+}
+
+impl Lease<'_> {
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the replica channel, creating it on first use.
+    pub(crate) async fn channel(&self, config: &AzConfig) -> Result<Channel, AzError> {
+        let replica = &self.balancer.replicas[self.index];
+        let channel = replica
+            .channel
+            .get_or_try_init(|| async { transport::connect_lazy(config, &replica.endpoint) })
+            .await?;
+
+        Ok(channel.clone())
+    }
+
+    /// Updates the replica health with the outcome of the call.
+    pub(crate) fn record<T>(mut self, result: &Result<T, AzError>) {
+        self.recorded = true;
+        let ejection = &self.balancer.ejection;
+        let mut health = util::lock(&self.balancer.replicas[self.index].health);
+
+        match result {
+            Err(err) if err.is_unavailable() => {
+                health.consecutive_failures += 1;
+                if health.probing || health.consecutive_failures >= ejection.consecutive_failures {
+                    health.ejections += 1;
+                    health.ejected_until = Some(Instant::now() + ejection.ejection_time(health.ejections));
+                    health.consecutive_failures = 0;
+                    health.probing = false;
+                }
+            }
+            // The replica answered, even if with an error.
+            _ => *health = Health::default(),
+        }
+    }
+}
+
+impl Drop for Lease<'_> {
+    fn drop(&mut self) {
+        let replica = &self.balancer.replicas[self.index];
+        replica.outstanding.fetch_sub(1, Ordering::Relaxed);
+        if !self.recorded {
+            // The caller gave up on the check: let another check probe the replica.
+            // Deadlines are recorded as failures before the lease is dropped.
+            util::lock(&replica.health).probing = false;
+        }
+    }
+}
+
+pub(crate) fn no_endpoint() -> AzError {
+    AzError::Config("no PDP endpoint configured".to_string())
+}
@@ -0,0 +1,138 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blocking client for codebases that do not run an async runtime.
+//!
+//! Enabled by the `blocking` feature. The client owns a small multi-threaded
+//! runtime, shared by its clones, so it can be used from many threads at once
+//! and keeps its connections alive between calls. It must not be used from
+//! within an async context.
+
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+use crate::az_client::{self, AzCallOptions};
+use crate::az_req::az_action::AzAction;
+use crate::az_req::matrix_builder::{AzMatrix, AzMatrixResponse};
+use crate::az_req::model;
+use crate::cache::CacheStats;
+use crate::circuit::CircuitState;
+use crate::config::AzConfig;
+use crate::error::AzError;
+use crate::interceptor::{AzInterceptor, TokenProvider};
+
+/// Defaults of the blocking client.
+pub mod blocking_default {
+    /// Number of runtime worker threads; checks are I/O bound, so a couple are enough.
+    pub const WORKER_THREADS: usize = 2;
+}
+
+/// Blocking counterpart of [`az_client::AzClient`].
+#[derive(Debug, Clone)]
+pub struct AzClient {
+    inner: az_client::AzClient,
+    runtime: Arc<Runtime>,
+}
+
+impl AzClient {
+    /// Creates a new client; the channel is opened lazily on the first check.
+    pub fn new(config: AzConfig) -> Result<Self, AzError> {
+        Ok(Self {
+            inner: az_client::AzClient::new(config),
+            runtime: Arc::new(Self::runtime()?),
+        })
+    }
+
+    /// Creates a new client and eagerly connects to the PDP.
+    pub fn connect(config: AzConfig) -> Result<Self, AzError> {
+        let runtime = Self::runtime()?;
+        let inner = runtime.block_on(az_client::AzClient::connect(config))?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Builds the runtime driving the channels; callers' threads only wait on its workers.
+    fn runtime() -> Result<Runtime, AzError> {
+        Ok(Builder::new_multi_thread()
+            .worker_threads(blocking_default::WORKER_THREADS)
+            .thread_name("permguard-blocking")
+            .enable_all()
+            .build()?)
+    }
+
+    /// Sets the provider of the bearer token sent with every RPC.
+    pub fn with_token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.inner = self.inner.with_token_provider(token_provider);
+        self
+    }
+
+    /// Adds an interceptor that edits the metadata of every RPC, after the headers and the token.
+    pub fn with_interceptor(mut self, interceptor: impl AzInterceptor + 'static) -> Self {
+        self.inner = self.inner.with_interceptor(interceptor);
+        self
+    }
+
+    /// Perform an authorization check via gRPC.
+    pub fn check_auth(&self, request: Option<model::AzRequest>) -> Result<model::AzResponse, AzError> {
+        self.runtime.block_on(self.inner.check_auth(request))
+    }
+
+    /// Perform an authorization check of a typed action, replacing the action of the request.
+    pub fn check_action(&self, request: model::AzRequest, action: &impl AzAction) -> Result<model::AzResponse, AzError> {
+        self.runtime.block_on(self.inner.check_action(request, action))
+    }
+
+    /// Evaluates every (subject, resource, action) combination of the matrix in one request.
+    pub fn check_matrix(&self, matrix: AzMatrix) -> Result<AzMatrixResponse, AzError> {
+        self.runtime.block_on(self.inner.check_matrix(matrix))
+    }
+
+    /// Perform an authorization check via gRPC with per-call options.
+    pub fn check_auth_with_options(
+        &self,
+        request: Option<model::AzRequest>,
+        options: AzCallOptions,
+    ) -> Result<model::AzResponse, AzError> {
+        self.runtime.block_on(self.inner.check_auth_with_options(request, options))
+    }
+
+    /// Returns the decision cache counters, if the cache is enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+
+    /// Removes every cached decision.
+    pub fn invalidate_cache(&self) {
+        self.inner.invalidate_cache();
+    }
+
+    /// Removes the cached decisions involving the subject.
+    pub fn invalidate_cache_for_subject(&self, subject_id: &str) {
+        self.inner.invalidate_cache_for_subject(subject_id);
+    }
+
+    /// Removes the cached decisions involving the resource.
+    pub fn invalidate_cache_for_resource(&self, resource_type: &str, resource_id: &str) {
+        self.inner.invalidate_cache_for_resource(resource_type, resource_id);
+    }
+
+    /// Returns the state of the circuit breaker, if it is enabled.
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.inner.circuit_state()
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use lru::LruCache;
+use crate::az_req::model::{AzRequest, AzResponse};
+use crate::config::CacheConfig;
+use crate::util;
+
+/// Snapshot of the decision cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    response: AzResponse,
+    expires_at: Instant,
+    subjects: Vec<String>,
+    resources: Vec<(String, String)>,
+}
+
+/// In-memory LRU cache of PDP decisions.
+#[derive(Debug)]
+pub(crate) struct DecisionCache {
+    config: CacheConfig,
+    entries: Mutex<LruCache<String, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DecisionCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.max_entries).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            config,
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cache key of a request.
+    pub(crate) fn key(&self, request: &AzRequest) -> String {
+        request.fingerprint_with(&self.config.fingerprint)
+    }
+
+    /// Returns the cached response for the key if it has not expired.
+    pub(crate) fn get(&self, key: &str) -> Option<AzResponse> {
+        let mut entries = util::lock(&self.entries);
+        let hit = match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.response.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if hit.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        hit
+    }
+
+    /// Stores the response of the request under the key.
+    pub(crate) fn insert(&self, key: String, request: &AzRequest, response: &AzResponse) {
+        let ttl = if response.decision { self.config.positive_ttl } else { self.config.negative_ttl };
+        if ttl.is_zero() {
+            return;
+        }
+
+        let evaluations = request.evaluations.iter().flatten();
+        let subjects = request
+            .subject
+            .iter()
+            .chain(evaluations.clone().filter_map(|e| e.subject.as_ref()))
+            .map(|s| s.id.clone())
+            .collect();
+        let resources = request
+            .resource
+            .iter()
+            .chain(evaluations.filter_map(|e| e.resource.as_ref()))
+            .map(|r| (r.r#type.clone(), r.id.clone()))
+            .collect();
+
+        let entry = CacheEntry {
+            response: response.clone(),
+            expires_at: Instant::now() + ttl,
+            subjects,
+            resources,
+        };
+        util::lock(&self.entries).put(key, entry);
+    }
+
+    /// Removes every cached decision.
+    pub(crate) fn invalidate_all(&self) {
+        util::lock(&self.entries).clear();
+    }
+
+    /// Removes the cached decisions involving the subject.
+    pub(crate) fn invalidate_subject(&self, subject_id: &str) {
+        self.invalidate_where(|entry| entry.subjects.iter().any(|id| id == subject_id));
+    }
+
+    /// Removes the cached decisions involving the resource.
+    pub(crate) fn invalidate_resource(&self, resource_type: &str, resource_id: &str) {
+        self.invalidate_where(|entry| {
+            entry
+                .resources
+                .iter()
+                .any(|(kind, id)| kind == resource_type && id == resource_id)
+        });
+    }
+
+    fn invalidate_where(&self, predicate: impl Fn(&CacheEntry) -> bool) {
+        let mut entries = util::lock(&self.entries);
+        let stale: Vec<String> = entries
+            .iter()
+            .filter(|(_, entry)| predicate(entry))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            entries.pop(&key);
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: util::lock(&self.entries).len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+    use super::*;
+    use crate::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+
+    fn request(subject: &str, resource_id: &str) -> AzRequest {
+        AzAtomicRequestBuilder::new(1, "ledger", subject, "App::Document", "App::Action::view")
+            .with_resource_id(resource_id)
+            .build()
+    }
+
+    fn response(decision: bool) -> AzResponse {
+        AzResponse {
+            request_id: String::new(),
+            decision,
+            context: None,
+            evaluations: Vec::new(),
+        }
+    }
+
+    fn cached(cache: &DecisionCache, request: &AzRequest, decision: bool) -> String {
+        let key = cache.key(request);
+        cache.insert(key.clone(), request, &response(decision));
+        key
+    }
+
+    #[test]
+    fn returns_a_cached_decision_until_it_expires() {
+        let cache = DecisionCache::new(CacheConfig::new().with_positive_ttl(Duration::from_millis(50)));
+        let key = cached(&cache, &request("amy", "d1"), true);
+
+        assert!(cache.get(&key).unwrap().decision);
+        sleep(Duration::from_millis(80));
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn uses_separate_ttls_for_permit_and_deny() {
+        let cache = DecisionCache::new(
+            CacheConfig::new()
+                .with_positive_ttl(Duration::from_secs(60))
+                .with_negative_ttl(Duration::from_millis(50)),
+        );
+        let permit = cached(&cache, &request("amy", "d1"), true);
+        let deny = cached(&cache, &request("bob", "d1"), false);
+
+        sleep(Duration::from_millis(80));
+        assert!(cache.get(&permit).is_some());
+        assert!(cache.get(&deny).is_none());
+    }
+
+    #[test]
+    fn does_not_store_decisions_with_a_zero_ttl() {
+        let cache = DecisionCache::new(CacheConfig::new().with_negative_ttl(Duration::ZERO));
+        let key = cached(&cache, &request("amy", "d1"), false);
+
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let cache = DecisionCache::new(CacheConfig::new().with_max_entries(2));
+        let first = cached(&cache, &request("amy", "d1"), true);
+        let second = cached(&cache, &request("amy", "d2"), true);
+
+        // Reading the first entry makes the second one the least recently used.
+        assert!(cache.get(&first).is_some());
+        let third = cached(&cache, &request("amy", "d3"), true);
+
+        assert!(cache.get(&first).is_some());
+        assert!(cache.get(&second).is_none());
+        assert!(cache.get(&third).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn invalidates_by_subject() {
+        let cache = DecisionCache::new(CacheConfig::new());
+        let amy = cached(&cache, &request("amy", "d1"), true);
+        let bob = cached(&cache, &request("bob", "d1"), true);
+
+        cache.invalidate_subject("amy");
+        assert!(cache.get(&amy).is_none());
+        assert!(cache.get(&bob).is_some());
+    }
+
+    #[test]
+    fn invalidates_by_resource() {
+        let cache = DecisionCache::new(CacheConfig::new());
+        let d1 = cached(&cache, &request("amy", "d1"), true);
+        let d2 = cached(&cache, &request("amy", "d2"), true);
+
+        cache.invalidate_resource("App::Document", "d1");
+        cache.invalidate_resource("App::Folder", "d2");
+        assert!(cache.get(&d1).is_none());
+        assert!(cache.get(&d2).is_some());
+    }
+
+    #[test]
+    fn invalidates_subjects_and_resources_of_evaluations() {
+        use crate::az_req::az_request_builder::AzRequestBuilder;
+        use crate::az_req::evaluation_builder::EvaluationBuilder;
+        use crate::az_req::resource_builder::ResourceBuilder;
+        use crate::az_req::subject_builder::SubjectBuilder;
+
+        let cache = DecisionCache::new(CacheConfig::new());
+        let evaluation = EvaluationBuilder::new(
+            Some(SubjectBuilder::new("carl").build()),
+            Some(ResourceBuilder::new("App::Document").with_id("d9").build()),
+            None,
+        )
+        .build();
+        let request = AzRequestBuilder::new(1, "ledger").with_evaluation(evaluation).build();
+
+        let key = cached(&cache, &request, true);
+        cache.invalidate_subject("carl");
+        assert!(cache.get(&key).is_none());
+
+        let key = cached(&cache, &request, true);
+        cache.invalidate_resource("App::Document", "d9");
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn invalidates_everything() {
+        let cache = DecisionCache::new(CacheConfig::new());
+        cached(&cache, &request("amy", "d1"), true);
+        cached(&cache, &request("bob", "d2"), false);
+
+        cache.invalidate_all();
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = DecisionCache::new(CacheConfig::new());
+        let key = cache.key(&request("amy", "d1"));
+
+        assert!(cache.get(&key).is_none());
+        cache.insert(key.clone(), &request("amy", "d1"), &response(true));
+        assert!(cache.get(&key).is_some());
+        assert!(cache.get(&key).is_some());
+
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 1, entries: 1 });
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Instant;
+use crate::config::CircuitBreakerConfig;
+use crate::error::AzError;
+use crate::util;
+
+/// State of the circuit breaker around PDP calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Checks are sent to the PDP.
+    Closed,
+    /// Checks fail without calling the PDP.
+    Open,
+    /// A limited number of probe checks are sent to the PDP.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: CircuitState,
+    /// Outcomes of the most recent checks, `true` for a failure.
+    window: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probes_started: u32,
+    probes_succeeded: u32,
+}
+
+/// Permission to call the PDP.
+///
+/// Dropping it without a verdict, when the caller gives up on the check, releases a probe slot.
+/// Deadlines are enforced before the permit is dropped, so a hung PDP is always recorded.
+pub(crate) struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                window: VecDeque::with_capacity(config.window_size),
+                opened_at: None,
+                probes_started: 0,
+                probes_succeeded: 0,
+            }),
+            config,
+        }
+    }
+
+    pub(crate) fn state(&self) -> CircuitState {
+        let mut inner = util::lock(&self.inner);
+        self.refresh(&mut inner);
+        inner.state
+    }
+
+    /// Returns a permit to call the PDP, or [`AzError::CircuitOpen`].
+    pub(crate) fn acquire(&self) -> Result<Permit<'_>, AzError> {
+        let mut inner = util::lock(&self.inner);
+        self.refresh(&mut inner);
+
+        match inner.state {
+            CircuitState::Closed => Ok(Permit { breaker: self, probe: false, recorded: false }),
+            CircuitState::HalfOpen if inner.probes_started < self.config.half_open_probes => {
+                inner.probes_started += 1;
+                Ok(Permit { breaker: self, probe: true, recorded: false })
+            }
+            _ => Err(AzError::CircuitOpen),
+        }
+    }
+
+    /// Moves an open breaker to half-open once its open duration has elapsed.
+    fn refresh(&self, inner: &mut Inner) {
+        if inner.state == CircuitState::Open
+            && let Some(opened_at) = inner.opened_at
+            && opened_at.elapsed() >= self.config.open_duration
+        {
+            inner.state = CircuitState::HalfOpen;
+            inner.probes_started = 0;
+            inner.probes_succeeded = 0;
+        }
+    }
+
+    fn open(inner: &mut Inner) {
+        inner.state = CircuitState::Open;
+        inner.opened_at = Some(Instant::now());
+        inner.window.clear();
+    }
+
+    fn record(&self, probe: bool, failed: bool) {
+        let mut inner = util::lock(&self.inner);
+
+        if probe {
+            if inner.state != CircuitState::HalfOpen {
+                return;
+            }
+            if failed {
+                Self::open(&mut inner);
+            } else {
+                inner.probes_succeeded += 1;
+                if inner.probes_succeeded >= self.config.half_open_probes {
+                    inner.state = CircuitState::Closed;
+                    inner.opened_at = None;
+                }
+            }
+            return;
+        }
+
+        if inner.state != CircuitState::Closed {
+            return;
+        }
+        if inner.window.len() == self.config.window_size {
+            inner.window.pop_front();
+        }
+        inner.window.push_back(failed);
+
+        // A window without failures never opens the breaker, even with a threshold of `0.0`.
+        let failures = inner.window.iter().filter(|failed| **failed).count();
+        if inner.window.len() == self.config.window_size
+            && failures > 0
+            && failures as f64 >= self.config.failure_rate_threshold * self.config.window_size as f64
+        {
+            Self::open(&mut inner);
+        }
+    }
+}
+
+impl Permit<'_> {
+    /// Records the outcome of the check; only unavailable errors count as failures.
+    pub(crate) fn record<T>(mut self, result: &Result<T, AzError>) {
+        self.recorded = true;
+        let failed = matches!(result, Err(err) if err.is_unavailable());
+        self.breaker.record(self.probe, failed);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            let mut inner = util::lock(&self.breaker.inner);
+            if inner.state == CircuitState::HalfOpen {
+                inner.probes_started = inner.probes_started.saturating_sub(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::*;
+
+    fn breaker(failure_rate_threshold: f64) -> CircuitBreaker {
+        CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .with_failure_rate_threshold(failure_rate_threshold)
+                .with_window_size(4)
+                .with_open_duration(Duration::from_secs(60)),
+        )
+    }
+
+    fn record(breaker: &CircuitBreaker, outcomes: &[bool]) {
+        for failed in outcomes {
+            let result: Result<(), AzError> = if *failed { Err(AzError::CircuitOpen) } else { Ok(()) };
+            breaker.acquire().unwrap().record(&result);
+        }
+    }
+
+    #[test]
+    fn stays_closed_on_successes_with_a_zero_threshold() {
+        let breaker = breaker(0.0);
+        record(&breaker, &[false; 8]);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        record(&breaker, &[true]);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn opens_once_the_full_window_reaches_the_threshold() {
+        let breaker = breaker(0.5);
+        record(&breaker, &[true, false, true]);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        record(&breaker, &[false]);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(breaker.acquire().map(|_| ()), Err(AzError::CircuitOpen)));
+    }
+
+    #[test]
+    fn ignores_errors_that_are_not_unavailability() {
+        let breaker = breaker(0.0);
+        for _ in 0..8 {
+            let result: Result<(), AzError> = Err(AzError::InvalidRequest("bad".to_string()));
+            breaker.acquire().unwrap().record(&result);
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
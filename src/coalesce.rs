@@ -0,0 +1,203 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+use tokio::sync::watch;
+use crate::az_req::model::AzResponse;
+use crate::error::AzError;
+use crate::util;
+
+type Outcome = Option<Result<AzResponse, AzError>>;
+
+enum Role {
+    Leader(watch::Sender<Outcome>),
+    Follower(watch::Receiver<Outcome>),
+}
+
+/// Single-flight registry of the authorization checks currently in flight.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    calls: Mutex<HashMap<String, watch::Receiver<Outcome>>>,
+}
+
+impl InFlight {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn calls(&self) -> MutexGuard<'_, HashMap<String, watch::Receiver<Outcome>>> {
+        util::lock(&self.calls)
+    }
+
+    /// Runs `call` unless an identical call is in flight, in which case its outcome is shared.
+    ///
+    /// If the leading call is cancelled or times out, one waiting caller becomes the new leader
+    /// and runs `call`, while the others wait for its outcome instead of calling the PDP too.
+    pub(crate) async fn run<F>(&self, key: String, call: F) -> Result<AzResponse, AzError>
+    where
+        F: Future<Output = Result<AzResponse, AzError>>,
+    {
+        loop {
+            let role = {
+                let mut calls = self.calls();
+                match calls.get(&key) {
+                    Some(receiver) => Role::Follower(receiver.clone()),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        calls.insert(key.clone(), receiver);
+                        Role::Leader(sender)
+                    }
+                }
+            };
+
+            match role {
+                Role::Leader(sender) => {
+                    let guard = LeaderGuard { in_flight: self, key: &key };
+                    let outcome = call.await;
+                    // Unregister first, so that followers woken by a timeout elect a new leader.
+                    drop(guard);
+                    let _ = sender.send(Some(outcome.clone()));
+                    return outcome;
+                }
+                Role::Follower(mut receiver) => {
+                    let shared = receiver
+                        .wait_for(Option::is_some)
+                        .await
+                        .ok()
+                        .and_then(|outcome| outcome.clone());
+                    match shared {
+                        // A timeout reflects the leader's deadline, not this caller's one.
+                        Some(Err(AzError::Timeout(_))) | None => continue,
+                        Some(outcome) => return outcome,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Unregisters the leading call when it completes or is cancelled.
+struct LeaderGuard<'a> {
+    in_flight: &'a InFlight,
+    key: &'a str,
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight.calls().remove(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use super::*;
+
+    fn response(decision: bool) -> AzResponse {
+        AzResponse { request_id: String::new(), decision, context: None, evaluations: Vec::new() }
+    }
+
+    /// Counts its calls and answers after `delay`, or never if `delay` is `None`.
+    async fn call(
+        calls: &AtomicUsize,
+        delay: Option<Duration>,
+        outcome: Result<AzResponse, AzError>,
+    ) -> Result<AzResponse, AzError> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        match delay {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => std::future::pending().await,
+        }
+        outcome
+    }
+
+    #[tokio::test]
+    async fn elects_a_single_new_leader_when_the_leader_is_cancelled() {
+        let in_flight = Arc::new(InFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let leader = {
+            let (in_flight, calls) = (in_flight.clone(), calls.clone());
+            tokio::spawn(async move { in_flight.run("key".to_string(), call(&calls, None, Ok(response(false)))).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let followers: Vec<_> = (0..8)
+            .map(|_| {
+                let (in_flight, calls) = (in_flight.clone(), calls.clone());
+                tokio::spawn(async move {
+                    in_flight
+                        .run("key".to_string(), call(&calls, Some(Duration::from_millis(50)), Ok(response(true))))
+                        .await
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        leader.abort();
+        for follower in followers {
+            assert!(follower.await.unwrap().unwrap().decision);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(in_flight.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn elects_a_single_new_leader_when_the_leader_times_out() {
+        let in_flight = Arc::new(InFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let run = |delay: u64, outcome: Result<AzResponse, AzError>| {
+            let (in_flight, calls) = (in_flight.clone(), calls.clone());
+            tokio::spawn(async move {
+                in_flight.run("key".to_string(), call(&calls, Some(Duration::from_millis(delay)), outcome)).await
+            })
+        };
+
+        let leader = run(50, Err(AzError::Timeout(Duration::from_millis(50))));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let followers: Vec<_> = (0..8).map(|_| run(20, Ok(response(true)))).collect();
+
+        assert!(matches!(leader.await.unwrap(), Err(AzError::Timeout(_))));
+        for follower in followers {
+            assert!(follower.await.unwrap().unwrap().decision);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn shares_errors_other_than_timeouts() {
+        let in_flight = Arc::new(InFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tasks: Vec<_> = (0..4)
+            .map(|_| {
+                let (in_flight, calls) = (in_flight.clone(), calls.clone());
+                let outcome = Err(AzError::Status { code: tonic::Code::PermissionDenied, message: "denied".to_string() });
+                tokio::spawn(async move {
+                    in_flight.run("key".to_string(), call(&calls, Some(Duration::from_millis(50)), outcome)).await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert!(matches!(task.await.unwrap(), Err(AzError::Status { code: tonic::Code::PermissionDenied, .. })));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
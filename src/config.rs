@@ -1,34 +1,858 @@
-#[derive(Debug, Clone)]
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use crate::az_req::fingerprint::FingerprintOptions;
+use crate::error::AzError;
+use crate::util;
+
+/// Default port of the Permguard PDP.
+pub const DEFAULT_PDP_PORT: u16 = 9094;
+
+/// Transport scheme used to reach the PDP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// Plain-text gRPC over TCP (`http` or `grpc`).
+    Http,
+    /// gRPC over TLS (`https` or `grpcs`).
+    Https,
+    /// gRPC over a Unix domain socket (`unix`).
+    Unix,
+}
+
+impl Scheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+            Scheme::Unix => "unix",
+        }
+    }
+
+    /// Returns the port used when an endpoint does not specify one.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Scheme::Http | Scheme::Https => DEFAULT_PDP_PORT,
+            Scheme::Unix => 0,
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = AzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "http" | "grpc" => Ok(Scheme::Http),
+            "https" | "grpcs" => Ok(Scheme::Https),
+            "unix" => Ok(Scheme::Unix),
+            _ => Err(AzError::InvalidEndpoint {
+                input: s.to_string(),
+                reason: "unsupported scheme, expected http, grpc, https, grpcs or unix".to_string(),
+            }),
+        }
+    }
+}
+
+/// Address of a PDP.
+///
+/// For [`Scheme::Unix`] the `host` holds the socket path and `port` is unused.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AzEndpoint {
-    pub schema: String,
+    pub scheme: Scheme,
     pub port: u16,
     pub host: String,
 }
 
 impl AzEndpoint {
-    pub fn new(schema: impl Into<String>, port: u16, host: impl Into<String>) -> Self {
-        let schema = schema.into();
+    /// Creates an endpoint from its parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheme is not supported or the host is empty; use
+    /// [`AzEndpoint::try_new`] or [`AzEndpoint::parse`] to handle these cases.
+    #[deprecated(note = "use `AzEndpoint::try_new` or `AzEndpoint::parse`, which return an error instead of panicking")]
+    pub fn new(scheme: impl Into<String>, port: u16, host: impl Into<String>) -> Self {
+        match Self::try_new(scheme, port, host) {
+            Ok(endpoint) => endpoint,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates an endpoint from its parts, failing on an unsupported scheme or an empty host.
+    pub fn try_new(scheme: impl Into<String>, port: u16, host: impl Into<String>) -> Result<Self, AzError> {
+        let scheme: Scheme = scheme.into().parse()?;
         let host = host.into();
+        if host.is_empty() {
+            return Err(AzError::InvalidEndpoint {
+                input: host,
+                reason: "host cannot be empty".to_string(),
+            });
+        }
+
+        let host = host.trim_start_matches('[').trim_end_matches(']').to_string();
+        Ok(Self { scheme, port, host })
+    }
+
+    /// Parses an endpoint such as `grpcs://pdp.internal:9094`, `http://[::1]` or `unix:///run/pdp.sock`.
+    ///
+    /// The port defaults to [`Scheme::default_port`] when omitted.
+    pub fn parse(input: &str) -> Result<Self, AzError> {
+        let invalid = |reason: &str| AzError::InvalidEndpoint {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let (scheme, rest) = input
+            .split_once(':')
+            .ok_or_else(|| invalid("missing scheme, expected `scheme://host:port`"))?;
+        let scheme: Scheme = scheme
+            .parse()
+            .map_err(|_| invalid(&format!("unsupported scheme `{}`", scheme)))?;
+
+        if scheme == Scheme::Unix {
+            // Accepts both `unix:///path` and `unix:/path`.
+            let path = rest.strip_prefix("//").unwrap_or(rest);
+            if path.is_empty() {
+                return Err(invalid("missing socket path"));
+            }
+            return Ok(Self { scheme, port: 0, host: path.to_string() });
+        }
+
+        let authority = rest
+            .strip_prefix("//")
+            .ok_or_else(|| invalid("expected `//` after the scheme"))?;
+        let authority = authority.strip_suffix('/').unwrap_or(authority);
+        if authority.contains('/') {
+            return Err(invalid("endpoint cannot contain a path"));
+        }
+
+        let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+            let (host, after) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("missing `]` after IPv6 address"))?;
+            match after {
+                "" => (host, None),
+                _ => (host, Some(after.strip_prefix(':').ok_or_else(|| invalid("unexpected text after IPv6 address"))?)),
+            }
+        } else {
+            match authority.split_once(':') {
+                Some((_, port)) if port.contains(':') => {
+                    return Err(invalid("IPv6 addresses must be enclosed in brackets"));
+                }
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+
+        if host.is_empty() {
+            return Err(invalid("host cannot be empty"));
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("port must be a number between 0 and 65535"))?,
+            None => scheme.default_port(),
+        };
+
+        Ok(Self { scheme, port, host: host.to_string() })
+    }
+
+    /// Returns the URI of the endpoint, bracketing IPv6 hosts.
+    pub fn url(&self) -> String {
+        match self.scheme {
+            Scheme::Unix => format!("unix://{}", self.host),
+            _ if self.host.contains(':') => format!("{}://[{}]:{}", self.scheme, self.host, self.port),
+            _ => format!("{}://{}:{}", self.scheme, self.host, self.port),
+        }
+    }
+}
+
+impl fmt::Display for AzEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url())
+    }
+}
+
+impl FromStr for AzEndpoint {
+    type Err = AzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for AzEndpoint {
+    type Error = AzError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+/// Source of PEM-encoded material, either a file on disk or in-memory bytes.
+#[derive(Clone)]
+pub enum PemSource {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl PemSource {
+    /// Loads the PEM bytes, reading the file if needed.
+    pub fn load(&self) -> Result<Vec<u8>, AzError> {
+        match self {
+            PemSource::File(path) => std::fs::read(path)
+                .map_err(|e| AzError::Config(format!("cannot read PEM file {}: {}", path.display(), e))),
+            PemSource::Bytes(bytes) => Ok(bytes.clone()),
+        }
+    }
+}
+
+impl fmt::Debug for PemSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PemSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            PemSource::Bytes(bytes) => write!(f, "Bytes(<{} bytes>)", bytes.len()),
+        }
+    }
+}
+
+/// Client certificate and private key used for mutual TLS.
+#[derive(Debug, Clone)]
+pub struct AzTlsIdentity {
+    pub cert: PemSource,
+    pub key: PemSource,
+}
+
+/// TLS settings applied to the channel towards the PDP.
+#[derive(Debug, Clone, Default)]
+pub struct AzTlsConfig {
+    /// CA bundle used to verify the PDP; the system roots are used when unset.
+    pub ca: Option<PemSource>,
+    /// Client identity presented to the PDP for mutual TLS.
+    pub identity: Option<AzTlsIdentity>,
+    /// Overrides the domain name used for SNI and certificate verification.
+    pub domain_name: Option<String>,
+}
+
+impl AzTlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the CA bundle from a PEM file.
+    pub fn with_ca_pem_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca = Some(PemSource::File(path.into()));
+        self
+    }
+
+    /// Sets the CA bundle from PEM bytes.
+    pub fn with_ca_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca = Some(PemSource::Bytes(pem.into()));
+        self
+    }
+
+    /// Sets the client certificate and key from PEM files.
+    pub fn with_identity_pem_files(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.identity = Some(AzTlsIdentity {
+            cert: PemSource::File(cert.into()),
+            key: PemSource::File(key.into()),
+        });
+        self
+    }
+
+    /// Sets the client certificate and key from PEM bytes.
+    pub fn with_identity_pem(mut self, cert: impl Into<Vec<u8>>, key: impl Into<Vec<u8>>) -> Self {
+        self.identity = Some(AzTlsIdentity {
+            cert: PemSource::Bytes(cert.into()),
+            key: PemSource::Bytes(key.into()),
+        });
+        self
+    }
+
+    /// Sets the domain name used for SNI and certificate verification.
+    pub fn with_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.domain_name = Some(domain_name.into());
+        self
+    }
+}
+
+/// Retry policy applied to PDP calls that fail with a transient error.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Backoff before the first retry; doubled on every following retry.
+    pub base_backoff: Duration,
+    /// Upper bound for the backoff between two attempts.
+    pub max_backoff: Duration,
+    /// Fraction (`0.0..=1.0`) of each backoff that is randomized.
+    pub jitter: f64,
+    /// gRPC codes that are considered transient.
+    pub retryable_codes: Vec<tonic::Code>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            jitter: 0.2,
+            retryable_codes: vec![tonic::Code::Unavailable],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the total number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the backoff before the first retry.
+    pub fn with_base_backoff(mut self, base_backoff: Duration) -> Self {
+        self.base_backoff = base_backoff;
+        self
+    }
+
+    /// Sets the upper bound for the backoff.
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the randomized fraction of each backoff, clamped to `0.0..=1.0`.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the gRPC codes that are retried.
+    pub fn with_retryable_codes(mut self, codes: Vec<tonic::Code>) -> Self {
+        self.retryable_codes = codes;
+        self
+    }
+
+    /// Returns whether the error is transient according to this policy.
+    pub fn is_retryable(&self, err: &AzError) -> bool {
+        match err {
+            AzError::Status { code, .. } => self.retryable_codes.contains(code),
+            _ => false,
+        }
+    }
+
+    /// Returns the backoff to wait after the given (1-based) failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_backoff
+            .saturating_mul(1u32 << exp)
+            .min(self.max_backoff);
+
+        if self.jitter <= 0.0 {
+            return delay;
+        }
+        delay.mul_f64(1.0 - self.jitter * util::random_unit())
+    }
+}
+
+/// Settings of the client-side decision cache.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// How long a permit decision is reused.
+    pub positive_ttl: Duration,
+    /// How long a deny decision is reused.
+    pub negative_ttl: Duration,
+    /// Maximum number of cached decisions; the least recently used is evicted first.
+    pub max_entries: usize,
+    /// Options of the request fingerprint used as cache key.
+    pub fingerprint: FingerprintOptions,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            positive_ttl: Duration::from_secs(30),
+            negative_ttl: Duration::from_secs(5),
+            max_entries: 10_000,
+            fingerprint: FingerprintOptions::default(),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long a permit decision is reused.
+    pub fn with_positive_ttl(mut self, ttl: Duration) -> Self {
+        self.positive_ttl = ttl;
+        self
+    }
+
+    /// Sets how long a deny decision is reused.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Sets the maximum number of cached decisions.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Sets the fingerprint options, e.g. to ignore volatile context keys.
+    pub fn with_fingerprint(mut self, fingerprint: FingerprintOptions) -> Self {
+        self.fingerprint = fingerprint;
+        self
+    }
+}
+
+/// Strategy used to pick a PDP endpoint when several are configured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BalancingStrategy {
+    /// Cycle through the endpoints in order.
+    #[default]
+    RoundRobin,
+    /// Pick an endpoint at random.
+    Random,
+    /// Pick the endpoint with the fewest calls in flight.
+    LeastOutstanding,
+}
+
+impl FromStr for BalancingStrategy {
+    type Err = String;
 
-        assert!(!schema.is_empty(), "schema cannot be empty");
-        assert!(!host.is_empty(), "host cannot be empty");
+    /// Parses `round-robin`, `random` or `least-outstanding` (case-insensitive, `_` allowed).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "round-robin" => Ok(BalancingStrategy::RoundRobin),
+            "random" => Ok(BalancingStrategy::Random),
+            "least-outstanding" => Ok(BalancingStrategy::LeastOutstanding),
+            other => Err(format!("unknown balancing strategy `{}`", other)),
+        }
+    }
+}
+
+/// When failing endpoints are taken out of rotation and probed again.
+///
+/// An endpoint is ejected after `consecutive_failures` unavailable errors in a
+/// row. Once its ejection time has elapsed a single check is routed to it as a
+/// probe: success puts it back in rotation, failure ejects it again for twice
+/// as long, up to `max_ejection_time`. When every endpoint is ejected the one
+/// due for a probe first is used anyway.
+#[derive(Debug, Clone)]
+pub struct EjectionPolicy {
+    /// Consecutive unavailable errors that eject an endpoint.
+    pub consecutive_failures: u32,
+    /// How long an endpoint stays ejected the first time.
+    pub base_ejection_time: Duration,
+    /// Upper bound for the ejection time of an endpoint that keeps failing.
+    pub max_ejection_time: Duration,
+}
+
+impl Default for EjectionPolicy {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 3,
+            base_ejection_time: Duration::from_secs(10),
+            max_ejection_time: Duration::from_secs(300),
+        }
+    }
+}
+
+impl EjectionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of consecutive unavailable errors that eject an endpoint.
+    pub fn with_consecutive_failures(mut self, consecutive_failures: u32) -> Self {
+        self.consecutive_failures = consecutive_failures.max(1);
+        self
+    }
+
+    /// Sets how long an endpoint stays ejected the first time.
+    pub fn with_base_ejection_time(mut self, base_ejection_time: Duration) -> Self {
+        self.base_ejection_time = base_ejection_time;
+        self
+    }
+
+    /// Sets the upper bound for the ejection time.
+    pub fn with_max_ejection_time(mut self, max_ejection_time: Duration) -> Self {
+        self.max_ejection_time = max_ejection_time;
+        self
+    }
+
+    /// Returns the ejection time after `ejections` consecutive ejections, starting at 1.
+    pub(crate) fn ejection_time(&self, ejections: u32) -> Duration {
+        self.base_ejection_time
+            .saturating_mul(2u32.saturating_pow(ejections.saturating_sub(1)))
+            .min(self.max_ejection_time)
+    }
+}
+
+/// Settings of the circuit breaker around PDP calls.
+///
+/// The breaker opens when at least `failure_rate_threshold` of the last
+/// `window_size` checks failed because the PDP was unavailable. While open,
+/// checks fail with [`AzError::CircuitOpen`] without calling the PDP. After
+/// `open_duration` up to `half_open_probes` checks are let through: if they
+/// all succeed the breaker closes, otherwise it opens again.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Fraction (`0.0..=1.0`) of failed checks that opens the breaker; `0.0` opens it on any failure.
+    pub failure_rate_threshold: f64,
+    /// Number of most recent checks the failure rate is computed on.
+    pub window_size: usize,
+    /// How long the breaker stays open before probing the PDP.
+    pub open_duration: Duration,
+    /// Number of checks let through while half-open.
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: 0.5,
+            window_size: 20,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 3,
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fraction of failed checks that opens the breaker, clamped to `0.0..=1.0`.
+    pub fn with_failure_rate_threshold(mut self, failure_rate_threshold: f64) -> Self {
+        self.failure_rate_threshold = failure_rate_threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the number of most recent checks the failure rate is computed on.
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self
+    }
+
+    /// Sets how long the breaker stays open before probing the PDP.
+    pub fn with_open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = open_duration;
+        self
+    }
+
+    /// Sets the number of checks let through while half-open.
+    pub fn with_half_open_probes(mut self, half_open_probes: u32) -> Self {
+        self.half_open_probes = half_open_probes.max(1);
+        self
+    }
+}
+
+/// Reason codes placed in synthesized responses when the PDP is unreachable.
+pub mod failure_default {
+    pub const REASON_CODE: &str = "PDP_UNREACHABLE";
+    pub const USER_MESSAGE: &str = "The authorization service is unavailable.";
+}
+
+/// Behavior of the client when the PDP cannot be reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Return the error to the caller.
+    #[default]
+    Propagate,
+    /// Answer with a synthesized deny decision.
+    FailClosed,
+    /// Answer with a synthesized permit decision.
+    FailOpen,
+}
+
+impl FromStr for FailureMode {
+    type Err = String;
 
-        Self { schema, port, host }
+    /// Parses `propagate`, `fail-closed` or `fail-open` (case-insensitive, `_` allowed).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "propagate" => Ok(FailureMode::Propagate),
+            "fail-closed" => Ok(FailureMode::FailClosed),
+            "fail-open" => Ok(FailureMode::FailOpen),
+            other => Err(format!("unknown failure mode `{}`", other)),
+        }
     }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct AzConfig {
     pub endpoint: Option<AzEndpoint>,
+    /// Additional PDP replicas, balanced together with `endpoint`.
+    pub endpoints: Vec<AzEndpoint>,
+    /// How a replica is picked for each call.
+    pub balancing_strategy: BalancingStrategy,
+    /// When failing replicas are taken out of rotation.
+    pub ejection_policy: EjectionPolicy,
+    pub tls: Option<AzTlsConfig>,
+    pub retry_policy: Option<RetryPolicy>,
+    /// Default deadline for an authorization check, retries included.
+    pub timeout: Option<Duration>,
+    /// Deadline for establishing the connection to the PDP.
+    pub connect_timeout: Option<Duration>,
+    /// What to answer when the PDP cannot be reached.
+    pub failure_mode: FailureMode,
+    /// Enables the client-side decision cache.
+    pub cache: Option<CacheConfig>,
+    /// Enables the circuit breaker around PDP calls.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Shares one PDP call between concurrent identical requests.
+    pub coalesce_requests: bool,
+    /// Sends requests without validating them first with [`AzRequest::validate`](crate::az_req::model::AzRequest::validate).
+    pub skip_request_validation: bool,
+    /// Metadata headers sent with every `AuthorizationCheck` RPC.
+    pub headers: Vec<(String, String)>,
+    /// Zone used for requests that do not set one.
+    pub zone_id: Option<i64>,
+    /// Ledger used for requests that do not set one.
+    pub ledger_id: Option<String>,
 }
 
 impl AzConfig {
     pub fn new() -> Self {
-        Self { endpoint: None }
+        Self::default()
     }
 
     pub fn with_endpoint(mut self, endpoint: Option<AzEndpoint>) -> Self {
         self.endpoint = endpoint;
         self
     }
-}
\ No newline at end of file
+
+    /// Sets additional PDP replicas, balanced together with the endpoint.
+    pub fn with_endpoints(mut self, endpoints: Vec<AzEndpoint>) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Sets how a replica is picked for each call.
+    pub fn with_balancing_strategy(mut self, balancing_strategy: BalancingStrategy) -> Self {
+        self.balancing_strategy = balancing_strategy;
+        self
+    }
+
+    /// Sets when failing replicas are taken out of rotation and probed again.
+    pub fn with_ejection_policy(mut self, ejection_policy: EjectionPolicy) -> Self {
+        self.ejection_policy = ejection_policy;
+        self
+    }
+
+    /// Returns every configured PDP endpoint, `endpoint` first.
+    pub fn all_endpoints(&self) -> impl Iterator<Item = &AzEndpoint> {
+        self.endpoint.iter().chain(self.endpoints.iter())
+    }
+
+    /// Sets the TLS settings; when present the PDP is reached over `https`.
+    pub fn with_tls(mut self, tls: Option<AzTlsConfig>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Sets the retry policy for transient PDP failures.
+    pub fn with_retry_policy(mut self, retry_policy: Option<RetryPolicy>) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the default deadline for an authorization check.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the deadline for establishing the connection to the PDP.
+    pub fn with_connect_timeout(mut self, connect_timeout: Option<Duration>) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sets the behavior when the PDP cannot be reached.
+    pub fn with_failure_mode(mut self, failure_mode: FailureMode) -> Self {
+        self.failure_mode = failure_mode;
+        self
+    }
+
+    /// Enables the client-side decision cache.
+    pub fn with_cache(mut self, cache: Option<CacheConfig>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Enables the circuit breaker around PDP calls.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Option<CircuitBreakerConfig>) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Enables single-flight coalescing of concurrent identical requests.
+    pub fn with_request_coalescing(mut self, enabled: bool) -> Self {
+        self.coalesce_requests = enabled;
+        self
+    }
+
+    /// Enables or disables client-side validation of requests; enabled by default.
+    pub fn with_request_validation(mut self, enabled: bool) -> Self {
+        self.skip_request_validation = !enabled;
+        self
+    }
+
+    /// Adds a metadata header sent with every `AuthorizationCheck` RPC, e.g. a tenant header.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the zone used for requests that do not set one.
+    pub fn with_zone_id(mut self, zone_id: Option<i64>) -> Self {
+        self.zone_id = zone_id;
+        self
+    }
+
+    /// Sets the ledger used for requests that do not set one.
+    pub fn with_ledger_id(mut self, ledger_id: Option<String>) -> Self {
+        self.ledger_id = ledger_id;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> AzEndpoint {
+        AzEndpoint::parse(input).unwrap_or_else(|e| panic!("`{}` should parse: {}", input, e))
+    }
+
+    fn parse_error(input: &str) -> String {
+        match AzEndpoint::parse(input) {
+            Err(AzError::InvalidEndpoint { reason, .. }) => reason,
+            other => panic!("`{}` should be an invalid endpoint, got {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn parses_host_and_port() {
+        let endpoint = parse("grpcs://pdp.internal:9443");
+        assert_eq!(endpoint, AzEndpoint { scheme: Scheme::Https, port: 9443, host: "pdp.internal".to_string() });
+        assert_eq!(parse("grpc://localhost:80").scheme, Scheme::Http);
+    }
+
+    #[test]
+    fn defaults_the_port_to_the_scheme_port() {
+        assert_eq!(parse("http://localhost").port, DEFAULT_PDP_PORT);
+        assert_eq!(parse("https://pdp.internal").port, Scheme::Https.default_port());
+    }
+
+    #[test]
+    fn parses_bracketed_ipv6_addresses() {
+        let endpoint = parse("grpc://[::1]:50051");
+        assert_eq!(endpoint.host, "::1");
+        assert_eq!(endpoint.port, 50051);
+        assert_eq!(endpoint.url(), "http://[::1]:50051");
+
+        let endpoint = parse("http://[fe80::1]");
+        assert_eq!(endpoint.host, "fe80::1");
+        assert_eq!(endpoint.port, DEFAULT_PDP_PORT);
+    }
+
+    #[test]
+    fn rejects_unbracketed_ipv6_addresses() {
+        assert!(parse_error("http://::1:80").contains("brackets"));
+    }
+
+    #[test]
+    fn rejects_malformed_ipv6_addresses() {
+        assert!(parse_error("http://[::1").contains("`]`"));
+        assert!(parse_error("http://[::1]80").contains("after IPv6"));
+    }
+
+    #[test]
+    fn parses_both_unix_socket_forms() {
+        for input in ["unix:///run/pdp.sock", "unix:/run/pdp.sock"] {
+            let endpoint = parse(input);
+            assert_eq!(endpoint.scheme, Scheme::Unix);
+            assert_eq!(endpoint.host, "/run/pdp.sock");
+            assert_eq!(endpoint.url(), "unix:///run/pdp.sock");
+        }
+        assert!(parse_error("unix://").contains("socket path"));
+    }
+
+    #[test]
+    fn accepts_a_trailing_slash_but_not_a_path() {
+        assert_eq!(parse("http://localhost:80/").host, "localhost");
+        assert!(parse_error("http://localhost:80/check").contains("path"));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(parse_error("ftp://localhost").contains("unsupported scheme"));
+        assert!(parse_error("localhost").contains("missing scheme"));
+        assert!(parse_error("http:localhost").contains("`//`"));
+        assert!(parse_error("http://:80").contains("host"));
+        assert!(parse_error("http://localhost:99999").contains("port"));
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for input in ["http://localhost:9094", "https://[::1]:443", "unix:///tmp/pdp.sock"] {
+            assert_eq!(parse(input).to_string(), input);
+            assert_eq!(parse(&parse(input).to_string()), parse(input));
+        }
+    }
+
+    #[test]
+    fn try_new_reports_errors_instead_of_panicking() {
+        assert!(matches!(AzEndpoint::try_new("ftp", 80, "localhost"), Err(AzError::InvalidEndpoint { .. })));
+        assert!(matches!(AzEndpoint::try_new("http", 80, ""), Err(AzError::InvalidEndpoint { .. })));
+        assert_eq!(AzEndpoint::try_new("https", 443, "[::1]").unwrap().host, "::1");
+    }
+
+    #[test]
+    fn doubles_the_backoff_up_to_the_cap() {
+        let policy = RetryPolicy::new()
+            .with_base_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(1000))
+            .with_jitter(0.0);
+
+        let backoffs: Vec<u128> = (1..=6).map(|attempt| policy.backoff(attempt).as_millis()).collect();
+        assert_eq!(backoffs, [100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn keeps_jittered_backoffs_within_the_cap() {
+        let policy = RetryPolicy::new()
+            .with_base_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(1000))
+            .with_jitter(0.5);
+
+        for attempt in 1..=40 {
+            let expected = Duration::from_millis(100).saturating_mul(1 << (attempt - 1).min(31)).min(Duration::from_millis(1000));
+            for _ in 0..20 {
+                let backoff = policy.backoff(attempt);
+                assert!(backoff <= expected, "attempt {}: {:?} above {:?}", attempt, backoff, expected);
+                assert!(backoff >= expected / 2, "attempt {}: {:?} below half of {:?}", attempt, backoff, expected);
+            }
+        }
+    }
+}
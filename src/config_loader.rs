@@ -0,0 +1,427 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+use std::time::Duration;
+use serde_json::{Map, Value};
+use crate::config::{AzConfig, AzEndpoint, AzTlsConfig, AzTlsIdentity, PemSource};
+use crate::error::{AzError, ConfigFieldError};
+
+/// Configuration keys with the environment variable each one is read from.
+const FIELDS: &[(&str, &str)] = &[
+    ("endpoint", "PERMGUARD_PDP_ENDPOINT"),
+    ("endpoints", "PERMGUARD_PDP_ENDPOINTS"),
+    ("balancing_strategy", "PERMGUARD_PDP_BALANCING_STRATEGY"),
+    ("timeout_ms", "PERMGUARD_PDP_TIMEOUT_MS"),
+    ("connect_timeout_ms", "PERMGUARD_PDP_CONNECT_TIMEOUT_MS"),
+    ("failure_mode", "PERMGUARD_PDP_FAILURE_MODE"),
+    ("tls.ca_file", "PERMGUARD_PDP_TLS_CA_FILE"),
+    ("tls.cert_file", "PERMGUARD_PDP_TLS_CERT_FILE"),
+    ("tls.key_file", "PERMGUARD_PDP_TLS_KEY_FILE"),
+    ("tls.domain_name", "PERMGUARD_PDP_TLS_DOMAIN_NAME"),
+    ("zone_id", "PERMGUARD_ZONE_ID"),
+    ("ledger_id", "PERMGUARD_LEDGER_ID"),
+];
+
+/// A set of raw configuration values read from a single source.
+struct Layer {
+    values: Vec<(String, &'static str, Value)>,
+}
+
+/// Layers applied in order of precedence, validated once all of them are merged.
+#[derive(Default)]
+struct Merged {
+    config: AzConfig,
+    cert_file: Option<PemSource>,
+    key_file: Option<PemSource>,
+    errors: Vec<ConfigFieldError>,
+}
+
+impl AzConfig {
+    /// Builds a configuration from the `PERMGUARD_*` environment variables.
+    pub fn from_env() -> Result<Self, AzError> {
+        Merged::default().apply(Layer::from_env(env_var)).finish()
+    }
+
+    /// Builds a configuration from a TOML or JSON file, chosen by extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AzError> {
+        Merged::default().apply(Layer::from_file(path.as_ref())?).finish()
+    }
+
+    /// Builds a configuration from an optional file overridden by the environment.
+    ///
+    /// Values set in code afterwards with the `with_*` methods take precedence over both.
+    pub fn load(path: Option<&Path>) -> Result<Self, AzError> {
+        load_layers(path, Layer::from_env(env_var))
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Applies the optional file, then the environment layer, and validates the result.
+fn load_layers(path: Option<&Path>, env: Layer) -> Result<AzConfig, AzError> {
+    let mut merged = Merged::default();
+    if let Some(path) = path {
+        merged = merged.apply(Layer::from_file(path)?);
+    }
+    merged.apply(env).finish()
+}
+
+impl Layer {
+    /// Reads the `PERMGUARD_*` variables through `var`, ignoring empty ones.
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let values = FIELDS
+            .iter()
+            .filter_map(|(key, name)| {
+                let value = var(name).filter(|v| !v.is_empty())?;
+                Some((name.to_string(), *key, Value::String(value)))
+            })
+            .collect();
+        Self { values }
+    }
+
+    fn from_file(path: &Path) -> Result<Self, AzError> {
+        let content = std::fs::read_to_string(path)?;
+        let field = |message: String| {
+            AzError::InvalidConfig(vec![ConfigFieldError {
+                field: path.display().to_string(),
+                message,
+            }])
+        };
+
+        let document: Value = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|e| field(e.to_string()))?,
+            Some("json") => serde_json::from_str(&content).map_err(|e| field(e.to_string()))?,
+            _ => return Err(field("expected a .toml or .json file".to_string())),
+        };
+        let Value::Object(document) = document else {
+            return Err(field("expected a table at the top level".to_string()));
+        };
+
+        let mut flat = Vec::new();
+        flatten("", document, &mut flat);
+        let values = flat
+            .into_iter()
+            .map(|(name, value)| {
+                let key = FIELDS.iter().map(|(key, _)| *key).find(|key| *key == name).unwrap_or("");
+                (name, key, value)
+            })
+            .collect();
+        Ok(Self { values })
+    }
+
+}
+
+impl Merged {
+    /// Applies the layer on top of the values merged so far, collecting every invalid field.
+    fn apply(mut self, layer: Layer) -> Self {
+        let Merged { config, cert_file, key_file, errors } = &mut self;
+
+        for (name, key, value) in layer.values {
+            let mut fail = |message: String| errors.push(ConfigFieldError { field: name.clone(), message });
+            match key {
+                "endpoint" => match as_string(&value).map(|s| AzEndpoint::parse(&s)) {
+                    Ok(Ok(endpoint)) => config.endpoint = Some(endpoint),
+                    Ok(Err(err)) => fail(err.to_string()),
+                    Err(message) => fail(message),
+                },
+                "endpoints" => match as_string_list(&value) {
+                    Ok(endpoints) => {
+                        let mut parsed = Vec::new();
+                        for endpoint in endpoints {
+                            match AzEndpoint::parse(&endpoint) {
+                                Ok(endpoint) => parsed.push(endpoint),
+                                Err(err) => fail(err.to_string()),
+                            }
+                        }
+                        config.endpoints = parsed;
+                    }
+                    Err(message) => fail(message),
+                },
+                "balancing_strategy" => match as_string(&value).and_then(|s| s.parse()) {
+                    Ok(strategy) => config.balancing_strategy = strategy,
+                    Err(message) => fail(message),
+                },
+                "timeout_ms" => match as_u64(&value) {
+                    Ok(ms) => config.timeout = Some(Duration::from_millis(ms)),
+                    Err(message) => fail(message),
+                },
+                "connect_timeout_ms" => match as_u64(&value) {
+                    Ok(ms) => config.connect_timeout = Some(Duration::from_millis(ms)),
+                    Err(message) => fail(message),
+                },
+                "failure_mode" => match as_string(&value).and_then(|s| s.parse()) {
+                    Ok(mode) => config.failure_mode = mode,
+                    Err(message) => fail(message),
+                },
+                "tls.ca_file" => match as_string(&value) {
+                    Ok(path) => config.tls.get_or_insert_with(AzTlsConfig::new).ca = Some(PemSource::File(path.into())),
+                    Err(message) => fail(message),
+                },
+                "tls.cert_file" => match as_string(&value) {
+                    Ok(path) => *cert_file = Some(PemSource::File(path.into())),
+                    Err(message) => fail(message),
+                },
+                "tls.key_file" => match as_string(&value) {
+                    Ok(path) => *key_file = Some(PemSource::File(path.into())),
+                    Err(message) => fail(message),
+                },
+                "tls.domain_name" => match as_string(&value) {
+                    Ok(domain) => config.tls.get_or_insert_with(AzTlsConfig::new).domain_name = Some(domain),
+                    Err(message) => fail(message),
+                },
+                "zone_id" => match as_u64(&value) {
+                    Ok(zone_id) if zone_id > 0 && zone_id <= i64::MAX as u64 => config.zone_id = Some(zone_id as i64),
+                    Ok(_) => fail("must be a positive zone ID".to_string()),
+                    Err(message) => fail(message),
+                },
+                "ledger_id" => match as_string(&value) {
+                    Ok(ledger_id) => config.ledger_id = Some(ledger_id),
+                    Err(message) => fail(message),
+                },
+                _ => fail("unknown field".to_string()),
+            }
+        }
+
+        self
+    }
+
+    /// Builds the client identity from the merged certificate and key, and reports every error.
+    fn finish(mut self) -> Result<AzConfig, AzError> {
+        match (self.cert_file, self.key_file) {
+            (Some(cert), Some(key)) => {
+                self.config.tls.get_or_insert_with(AzTlsConfig::new).identity = Some(AzTlsIdentity { cert, key });
+            }
+            (Some(_), None) => self.errors.push(ConfigFieldError {
+                field: "tls.key_file".to_string(),
+                message: "required when a client certificate is set".to_string(),
+            }),
+            (None, Some(_)) => self.errors.push(ConfigFieldError {
+                field: "tls.cert_file".to_string(),
+                message: "required when a client key is set".to_string(),
+            }),
+            (None, None) => {}
+        }
+
+        if self.errors.is_empty() {
+            Ok(self.config)
+        } else {
+            Err(AzError::InvalidConfig(self.errors))
+        }
+    }
+}
+
+/// Flattens nested tables into dotted keys, e.g. `tls.ca_file`.
+fn flatten(prefix: &str, table: Map<String, Value>, out: &mut Vec<(String, Value)>) {
+    for (key, value) in table {
+        let name = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+        match value {
+            Value::Object(inner) => flatten(&name, inner, out),
+            value => out.push((name, value)),
+        }
+    }
+}
+
+fn as_string(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Ok(s.clone()),
+        Value::String(_) => Err("must not be empty".to_string()),
+        _ => Err("expected a string".to_string()),
+    }
+}
+
+/// Reads an array of strings, or a comma-separated string as found in the environment.
+fn as_string_list(value: &Value) -> Result<Vec<String>, String> {
+    let items: Vec<String> = match value {
+        Value::String(s) => s.split(',').map(|item| item.trim().to_string()).collect(),
+        Value::Array(items) => items.iter().map(as_string).collect::<Result<_, _>>()?,
+        _ => return Err("expected a list of strings".to_string()),
+    };
+    if items.iter().any(|item| item.is_empty()) {
+        return Err("must not contain empty items".to_string());
+    }
+    Ok(items)
+}
+
+fn as_u64(value: &Value) -> Result<u64, String> {
+    match value {
+        Value::Number(n) => n.as_u64().ok_or_else(|| "expected a non-negative integer".to_string()),
+        Value::String(s) => s.trim().parse().map_err(|_| format!("expected a non-negative integer, got `{}`", s)),
+        _ => Err("expected a non-negative integer".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use super::*;
+    use crate::config::{BalancingStrategy, FailureMode, Scheme};
+
+    fn env(vars: &[(&str, &str)]) -> Layer {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Layer::from_env(|name| vars.get(name).cloned())
+    }
+
+    /// Writes `content` to a file unique to the test, with the given extension.
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("permguard-config-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn fields(result: Result<AzConfig, AzError>) -> Vec<(String, String)> {
+        match result {
+            Err(AzError::InvalidConfig(errors)) => errors.into_iter().map(|e| (e.field, e.message)).collect(),
+            other => panic!("expected an invalid configuration, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    fn identity(config: &AzConfig) -> String {
+        format!("{:?}", config.tls.as_ref().and_then(|tls| tls.identity.as_ref()))
+    }
+
+    #[test]
+    fn reads_the_environment() {
+        let config = load_layers(
+            None,
+            env(&[
+                ("PERMGUARD_PDP_ENDPOINTS", "http://pdp-a:9094, https://pdp-b:9094"),
+                ("PERMGUARD_PDP_BALANCING_STRATEGY", "least_outstanding"),
+                ("PERMGUARD_PDP_TIMEOUT_MS", "250"),
+                ("PERMGUARD_PDP_FAILURE_MODE", "FAIL-CLOSED"),
+                ("PERMGUARD_PDP_TLS_DOMAIN_NAME", "pdp.internal"),
+                ("PERMGUARD_ZONE_ID", "42"),
+                ("PERMGUARD_LEDGER_ID", ""),
+            ]),
+        )
+        .unwrap();
+
+        let hosts: Vec<_> = config.endpoints.iter().map(|e| (e.scheme, e.host.as_str())).collect();
+        assert_eq!(hosts, [(Scheme::Http, "pdp-a"), (Scheme::Https, "pdp-b")]);
+        assert_eq!(config.balancing_strategy, BalancingStrategy::LeastOutstanding);
+        assert_eq!(config.timeout, Some(Duration::from_millis(250)));
+        assert_eq!(config.failure_mode, FailureMode::FailClosed);
+        assert_eq!(config.tls.unwrap().domain_name.as_deref(), Some("pdp.internal"));
+        assert_eq!(config.zone_id, Some(42));
+        assert_eq!(config.ledger_id, None);
+    }
+
+    #[test]
+    fn reads_toml_and_json_files() {
+        let toml = file(
+            "read.toml",
+            r#"
+            endpoint = "grpcs://pdp.internal:9443"
+            connect_timeout_ms = 100
+            zone_id = 7
+
+            [tls]
+            ca_file = "/etc/pdp/ca.pem"
+            cert_file = "/etc/pdp/client.pem"
+            key_file = "/etc/pdp/client.key"
+            "#,
+        );
+        let json = file("read.json", r#"{"endpoints": ["http://a:1", "http://b:2"], "ledger_id": "l1"}"#);
+
+        let config = AzConfig::from_file(&toml).unwrap();
+        let endpoint = config.endpoint.as_ref().unwrap();
+        assert_eq!((endpoint.scheme, endpoint.host.as_str(), endpoint.port), (Scheme::Https, "pdp.internal", 9443));
+        assert_eq!(config.connect_timeout, Some(Duration::from_millis(100)));
+        assert_eq!(config.zone_id, Some(7));
+        assert_eq!(
+            identity(&config),
+            r#"Some(AzTlsIdentity { cert: File("/etc/pdp/client.pem"), key: File("/etc/pdp/client.key") })"#
+        );
+
+        let config = AzConfig::from_file(&json).unwrap();
+        assert_eq!(config.endpoints.len(), 2);
+        assert_eq!(config.ledger_id.as_deref(), Some("l1"));
+
+        let yaml = file("read.yaml", "zone_id: 7");
+        assert_eq!(fields(AzConfig::from_file(&yaml)), [(yaml.display().to_string(), "expected a .toml or .json file".to_string())]);
+        let broken = file("broken.json", "{");
+        assert_eq!(fields(AzConfig::from_file(&broken))[0].0, broken.display().to_string());
+
+        for path in [toml, json, yaml, broken] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn lets_the_environment_override_the_file() {
+        let path = file("precedence.toml", "zone_id = 7\nledger_id = \"from-file\"\ntimeout_ms = 100\n");
+
+        let config = load_layers(Some(&path), env(&[("PERMGUARD_ZONE_ID", "8"), ("PERMGUARD_PDP_TIMEOUT_MS", "200")])).unwrap();
+        assert_eq!(config.zone_id, Some(8));
+        assert_eq!(config.timeout, Some(Duration::from_millis(200)));
+        assert_eq!(config.ledger_id.as_deref(), Some("from-file"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_key_of_an_earlier_layer_when_only_the_certificate_is_overridden() {
+        let path = file("identity.toml", "[tls]\ncert_file = \"/file/client.pem\"\nkey_file = \"/file/client.key\"\n");
+
+        let config = load_layers(Some(&path), env(&[("PERMGUARD_PDP_TLS_CERT_FILE", "/env/client.pem")])).unwrap();
+        assert_eq!(
+            identity(&config),
+            r#"Some(AzTlsIdentity { cert: File("/env/client.pem"), key: File("/file/client.key") })"#
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let path = file("identity-split.toml", "[tls]\ncert_file = \"/file/client.pem\"\n");
+        let config = load_layers(Some(&path), env(&[("PERMGUARD_PDP_TLS_KEY_FILE", "/env/client.key")])).unwrap();
+        assert_eq!(
+            identity(&config),
+            r#"Some(AzTlsIdentity { cert: File("/file/client.pem"), key: File("/env/client.key") })"#
+        );
+
+        assert_eq!(
+            fields(load_layers(Some(&path), env(&[]))),
+            [("tls.key_file".to_string(), "required when a client certificate is set".to_string())]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn collects_every_invalid_field() {
+        let path = file("invalid.json", r#"{"zone_id": 0, "timeout_ms": -1, "tls": {"key_file": "k.pem", "pin": "x"}}"#);
+
+        let mut errors = fields(load_layers(
+            Some(&path),
+            env(&[("PERMGUARD_PDP_ENDPOINT", "ftp://pdp"), ("PERMGUARD_PDP_FAILURE_MODE", "maybe")]),
+        ));
+        errors.sort();
+        let errors: Vec<(&str, &str)> = errors.iter().map(|(f, m)| (f.as_str(), m.as_str())).collect();
+        assert_eq!(
+            errors,
+            [
+                ("PERMGUARD_PDP_ENDPOINT", "invalid endpoint `ftp://pdp`: unsupported scheme `ftp`"),
+                ("PERMGUARD_PDP_FAILURE_MODE", "unknown failure mode `maybe`"),
+                ("timeout_ms", "expected a non-negative integer"),
+                ("tls.cert_file", "required when a client key is set"),
+                ("tls.pin", "unknown field"),
+                ("zone_id", "must be a positive zone ID"),
+            ]
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
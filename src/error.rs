@@ -0,0 +1,165 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A configuration field that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFieldError {
+    /// Name of the field, or of the environment variable it was read from.
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// A field of an authorization request that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Path of the field, e.g. `evaluations[0].action.name`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Errors returned by the Permguard SDK.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum AzError {
+    /// The client configuration is missing or invalid.
+    Config(String),
+    /// One or more configuration fields are invalid.
+    InvalidConfig(Vec<ConfigFieldError>),
+    /// A PDP endpoint could not be parsed.
+    InvalidEndpoint { input: String, reason: String },
+    /// A Cedar entity UID could not be parsed or has invalid identifiers.
+    InvalidEntityUid { input: String, reason: String },
+    /// The authorization request is missing or malformed.
+    InvalidRequest(String),
+    /// The authorization request failed client-side validation.
+    Validation(Vec<ValidationError>),
+    /// A JWT could not be decoded, verified or mapped.
+    InvalidToken(String),
+    /// An I/O operation of the SDK failed.
+    Io(Arc<std::io::Error>),
+    /// The connection to the PDP could not be established.
+    Transport(Arc<tonic::transport::Error>),
+    /// The PDP answered with a gRPC error status.
+    Status { code: tonic::Code, message: String },
+    /// The PDP response does not match the request it answers.
+    ResponseMismatch(String),
+    /// The call did not complete within its deadline.
+    Timeout(Duration),
+    /// The circuit breaker is open and the PDP was not called.
+    CircuitOpen,
+    /// The call still failed after `attempts` attempts; `last` is the final error.
+    Retried { attempts: u32, last: Box<AzError> },
+}
+
+impl AzError {
+    /// Returns whether the error means the PDP could not be reached.
+    ///
+    /// Configuration errors, such as an invalid TLS identity, are never unavailability, so they
+    /// are not turned into decisions by [`FailureMode`](crate::config::FailureMode).
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            AzError::Transport(_) | AzError::Timeout(_) | AzError::CircuitOpen => true,
+            AzError::Status { code, .. } => {
+                matches!(code, tonic::Code::Unavailable | tonic::Code::DeadlineExceeded)
+            }
+            AzError::Retried { last, .. } => last.is_unavailable(),
+            _ => false,
+        }
+    }
+
+    /// Returns how many attempts were made before this error was returned.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            AzError::Retried { attempts, .. } => *attempts,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for AzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AzError::Config(msg) => write!(f, "invalid configuration: {}", msg),
+            AzError::InvalidConfig(fields) => {
+                let fields: Vec<String> = fields.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid configuration: {}", fields.join("; "))
+            }
+            AzError::InvalidEndpoint { input, reason } => write!(f, "invalid endpoint `{}`: {}", input, reason),
+            AzError::InvalidEntityUid { input, reason } => write!(f, "invalid entity UID `{}`: {}", input, reason),
+            AzError::InvalidRequest(msg) => write!(f, "invalid request: {}", msg),
+            AzError::Validation(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid request: {}", errors.join("; "))
+            }
+            AzError::InvalidToken(msg) => write!(f, "invalid token: {}", msg),
+            AzError::Io(err) => write!(f, "I/O error: {}", err),
+            AzError::Transport(err) => write!(f, "transport error: {}", err),
+            AzError::Status { code, message } => write!(f, "PDP returned status {:?}: {}", code, message),
+            AzError::ResponseMismatch(msg) => write!(f, "response mismatch: {}", msg),
+            AzError::Timeout(timeout) => write!(f, "deadline of {:?} exceeded", timeout),
+            AzError::CircuitOpen => write!(f, "circuit breaker is open, the PDP was not called"),
+            AzError::Retried { attempts, last } => write!(f, "failed after {} attempts: {}", attempts, last),
+        }
+    }
+}
+
+impl std::error::Error for AzError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AzError::Io(err) => Some(err.as_ref()),
+            AzError::Transport(err) => Some(err.as_ref()),
+            AzError::Retried { last, .. } => Some(last.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AzError {
+    fn from(err: std::io::Error) -> Self {
+        AzError::Io(Arc::new(err))
+    }
+}
+
+impl From<tonic::transport::Error> for AzError {
+    fn from(err: tonic::transport::Error) -> Self {
+        AzError::Transport(Arc::new(err))
+    }
+}
+
+impl From<tonic::Status> for AzError {
+    fn from(status: tonic::Status) -> Self {
+        AzError::Status {
+            code: status.code(),
+            message: status.message().to_string(),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap};
+use crate::error::AzError;
+
+/// Future returned by [`TokenProvider::token`].
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = Result<String, AzError>> + Send + 'a>>;
+
+/// Supplies the bearer token sent with every `AuthorizationCheck` RPC.
+///
+/// The provider is asked for a token before each attempt, so it can cache the
+/// token and refresh it when it expires.
+pub trait TokenProvider: Send + Sync {
+    fn token(&self) -> TokenFuture<'_>;
+}
+
+impl<F, Fut> TokenProvider for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String, AzError>> + Send + 'static,
+{
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(self())
+    }
+}
+
+/// Hook that edits the metadata of every `AuthorizationCheck` RPC.
+pub trait AzInterceptor: Send + Sync {
+    fn intercept(&self, metadata: &mut MetadataMap) -> Result<(), AzError>;
+}
+
+impl<F> AzInterceptor for F
+where
+    F: Fn(&mut MetadataMap) -> Result<(), AzError> + Send + Sync,
+{
+    fn intercept(&self, metadata: &mut MetadataMap) -> Result<(), AzError> {
+        self(metadata)
+    }
+}
+
+/// Token provider and interceptors registered on a client.
+#[derive(Clone, Default)]
+pub(crate) struct Interceptors {
+    pub(crate) token_provider: Option<Arc<dyn TokenProvider>>,
+    pub(crate) interceptors: Vec<Arc<dyn AzInterceptor>>,
+}
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interceptors")
+            .field("token_provider", &self.token_provider.is_some())
+            .field("interceptors", &self.interceptors.len())
+            .finish()
+    }
+}
+
+impl Interceptors {
+    /// Adds the static headers, the bearer token and the interceptor edits to `metadata`.
+    pub(crate) async fn apply(&self, headers: &[(String, String)], metadata: &mut MetadataMap) -> Result<(), AzError> {
+        for (name, value) in headers {
+            metadata.append(metadata_key(name)?, metadata_value(name, value)?);
+        }
+
+        if let Some(token_provider) = &self.token_provider {
+            let token = token_provider.token().await?;
+            metadata.insert("authorization", metadata_value("authorization", &format!("Bearer {}", token))?);
+        }
+
+        for interceptor in &self.interceptors {
+            interceptor.intercept(metadata)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn metadata_key(name: &str) -> Result<AsciiMetadataKey, AzError> {
+    AsciiMetadataKey::from_bytes(name.as_bytes())
+        .map_err(|_| AzError::Config(format!("invalid metadata header name `{}`", name)))
+}
+
+fn metadata_value(name: &str, value: &str) -> Result<AsciiMetadataValue, AzError> {
+    AsciiMetadataValue::try_from(value).map_err(|_| AzError::Config(format!("invalid value for metadata header `{}`", name)))
+}
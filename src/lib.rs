@@ -1,5 +1,25 @@
 pub mod az_req;
 pub mod az_client;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod balancer;
+pub mod cache;
+pub mod circuit;
+mod coalesce;
 pub mod mapper;
 pub mod config;
+mod config_loader;
+pub mod error;
+pub mod interceptor;
+mod transport;
+mod util;
 
+
+#[cfg(feature = "derive")]
+pub use permguard_derive::{AzAction, AzResource, AzSubject, CedarEntity};
+
+/// Re-exports used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
@@ -18,6 +18,7 @@ use std::collections::{HashMap, BTreeMap};
 use prost_types::{Struct, Value, ListValue};
 use crate::az_client::policydecisionpoint;
 use crate::az_req::model::*;
+use crate::error::AzError;
 
 pub struct MapService;
 
@@ -39,11 +40,9 @@ impl MapService {
     pub fn to_repeated_struct(items: Option<&Vec<Option<HashMap<String, serde_json::Value>>>>) -> Vec<Struct> {
         let mut list = Vec::new();
         if let Some(vec) = items {
-            for item in vec {
-                if let Some(map) = item {
-                    if let Some(s) = Self::from_dictionary(Some(map)) {
-                        list.push(s);
-                    }
+            for map in vec.iter().flatten() {
+                if let Some(s) = Self::from_dictionary(Some(map)) {
+                    list.push(s);
                 }
             }
         }
@@ -121,8 +120,8 @@ impl MapService {
             id: p.id.clone(),
             r#type: p.r#type.clone(),
             source: p.source.clone(),
-            identity_token: None,
-            access_token: None,
+            identity_token: p.identity_token.clone(),
+            access_token: p.access_token.clone(),
         })
     }
 
@@ -239,4 +238,76 @@ impl MapService {
                 .collect(),
         }
     }
+
+    /// Verifies that a mapped response answers the given request.
+    pub fn verify_response(req: &AzRequest, resp: &AzResponse) -> Result<(), AzError> {
+        let expected = req.evaluations.as_ref().map_or(0, |evs| evs.len());
+        if expected > 0 && resp.evaluations.len() != expected {
+            return Err(AzError::ResponseMismatch(format!(
+                "expected {} evaluations, got {}",
+                expected,
+                resp.evaluations.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+    use crate::az_req::principal_builder::PrincipalBuilder;
+
+    const IDENTITY_TOKEN: &str = "eyJhbGciOiJSUzI1NiJ9.identity";
+    const ACCESS_TOKEN: &str = "eyJhbGciOiJSUzI1NiJ9.access";
+
+    fn principal_builder() -> PrincipalBuilder {
+        PrincipalBuilder::new("amy.smith@acmecorp.com")
+            .with_source("keycloak")
+            .with_identity_token(IDENTITY_TOKEN)
+            .with_access_token(ACCESS_TOKEN)
+    }
+
+    fn request() -> AzRequest {
+        AzAtomicRequestBuilder::new(1, "ledger", "amy.smith@acmecorp.com", "App::Document", "App::Action::view")
+            .with_principal(principal_builder().build())
+            .build()
+    }
+
+    #[test]
+    fn maps_every_principal_field_to_grpc() {
+        let grpc_principal = MapService::map_az_request(&request()).authorization_model.unwrap().principal.unwrap();
+
+        assert_eq!(grpc_principal.r#type, "user");
+        assert_eq!(grpc_principal.id, "amy.smith@acmecorp.com");
+        assert_eq!(grpc_principal.source.as_deref(), Some("keycloak"));
+        assert_eq!(grpc_principal.identity_token.as_deref(), Some(IDENTITY_TOKEN));
+        assert_eq!(grpc_principal.access_token.as_deref(), Some(ACCESS_TOKEN));
+    }
+
+    #[test]
+    fn leaves_missing_tokens_unset() {
+        let request = AzAtomicRequestBuilder::new(1, "ledger", "amy", "App::Document", "App::Action::view")
+            .with_principal(PrincipalBuilder::new("amy").build())
+            .build();
+
+        let grpc_principal = MapService::map_az_request(&request).authorization_model.unwrap().principal.unwrap();
+        assert_eq!(grpc_principal.identity_token, None);
+        assert_eq!(grpc_principal.access_token, None);
+    }
+
+    #[test]
+    fn redacts_tokens_in_debug_output() {
+        let request = request();
+        for debug in [
+            format!("{:?}", principal_builder()),
+            format!("{:?}", request.authorization_model.principal),
+            format!("{:?}", request),
+        ] {
+            assert!(!debug.contains(IDENTITY_TOKEN), "identity token leaked: {}", debug);
+            assert!(!debug.contains(ACCESS_TOKEN), "access token leaked: {}", debug);
+            assert!(debug.contains("<redacted>"), "tokens not shown as redacted: {}", debug);
+        }
+    }
 }
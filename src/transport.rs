@@ -0,0 +1,143 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use crate::config::{AzConfig, AzEndpoint, AzTlsConfig, Scheme};
+use crate::error::AzError;
+
+/// Placeholder URI for Unix socket channels; it only provides the `:authority` header.
+const UNIX_AUTHORITY_URI: &str = "http://localhost";
+
+/// Opens a channel to the endpoint, connecting on first use.
+pub(crate) fn connect_lazy(config: &AzConfig, endpoint: &AzEndpoint) -> Result<Channel, AzError> {
+    let grpc_endpoint = grpc_endpoint(config, endpoint)?;
+    match endpoint.scheme {
+        Scheme::Unix => unix::connect_lazy(&grpc_endpoint, &endpoint.host),
+        _ => Ok(grpc_endpoint.connect_lazy()),
+    }
+}
+
+/// Opens a channel to the endpoint, connecting eagerly.
+pub(crate) async fn connect(config: &AzConfig, endpoint: &AzEndpoint) -> Result<Channel, AzError> {
+    let grpc_endpoint = grpc_endpoint(config, endpoint)?;
+    match endpoint.scheme {
+        Scheme::Unix => unix::connect(&grpc_endpoint, &endpoint.host).await,
+        _ => Ok(grpc_endpoint.connect().await?),
+    }
+}
+
+/// Builds the tonic endpoint, applying TLS and timeouts from the configuration.
+///
+/// Errors are local configuration mistakes, reported as [`AzError::Config`] so that they are
+/// never mistaken for an unreachable PDP.
+fn grpc_endpoint(config: &AzConfig, endpoint: &AzEndpoint) -> Result<Endpoint, AzError> {
+    let mut grpc_endpoint = match endpoint.scheme {
+        Scheme::Unix => Endpoint::from_static(UNIX_AUTHORITY_URI),
+        _ => {
+            let mut endpoint = endpoint.clone();
+            if config.tls.is_some() {
+                endpoint.scheme = Scheme::Https;
+            }
+            Endpoint::from_shared(endpoint.url())
+                .map_err(|e| AzError::Config(format!("invalid endpoint `{}`: {}", endpoint, e)))?
+        }
+    };
+
+    if let Some(connect_timeout) = config.connect_timeout {
+        grpc_endpoint = grpc_endpoint.connect_timeout(connect_timeout);
+    }
+
+    if let Some(tls) = &config.tls
+        && endpoint.scheme != Scheme::Unix
+    {
+        grpc_endpoint = grpc_endpoint
+            .tls_config(client_tls_config(tls)?)
+            .map_err(|e| AzError::Config(format!("invalid TLS configuration: {}", tls_error(&e))))?;
+    }
+
+    Ok(grpc_endpoint)
+}
+
+/// Returns the innermost cause of a TLS error, e.g. the PEM parsing failure.
+fn tls_error(err: &tonic::transport::Error) -> String {
+    let mut source: &dyn std::error::Error = err;
+    while let Some(inner) = source.source() {
+        source = inner;
+    }
+    source.to_string()
+}
+
+fn client_tls_config(tls: &AzTlsConfig) -> Result<ClientTlsConfig, AzError> {
+    let mut client_tls = ClientTlsConfig::new();
+
+    client_tls = match &tls.ca {
+        Some(ca) => client_tls.ca_certificate(Certificate::from_pem(ca.load()?)),
+        None => client_tls.with_native_roots(),
+    };
+
+    if let Some(identity) = &tls.identity {
+        client_tls = client_tls.identity(Identity::from_pem(identity.cert.load()?, identity.key.load()?));
+    }
+
+    if let Some(domain_name) = &tls.domain_name {
+        client_tls = client_tls.domain_name(domain_name.clone());
+    }
+
+    Ok(client_tls)
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::path::PathBuf;
+    use hyper_util::rt::TokioIo;
+    use tokio::net::UnixStream;
+    use tonic::transport::{Channel, Endpoint, Uri};
+    use tower::service_fn;
+    use crate::error::AzError;
+
+    pub(super) fn connect_lazy(endpoint: &Endpoint, path: &str) -> Result<Channel, AzError> {
+        let path = PathBuf::from(path);
+        Ok(endpoint.connect_with_connector_lazy(service_fn(move |_: Uri| {
+            let path = path.clone();
+            async move { Ok::<_, std::io::Error>(TokioIo::new(UnixStream::connect(path).await?)) }
+        })))
+    }
+
+    pub(super) async fn connect(endpoint: &Endpoint, path: &str) -> Result<Channel, AzError> {
+        let path = PathBuf::from(path);
+        let channel = endpoint
+            .connect_with_connector(service_fn(move |_: Uri| {
+                let path = path.clone();
+                async move { Ok::<_, std::io::Error>(TokioIo::new(UnixStream::connect(path).await?)) }
+            }))
+            .await?;
+        Ok(channel)
+    }
+}
+
+#[cfg(not(unix))]
+mod unix {
+    use tonic::transport::{Channel, Endpoint};
+    use crate::error::AzError;
+
+    pub(super) fn connect_lazy(_endpoint: &Endpoint, _path: &str) -> Result<Channel, AzError> {
+        Err(AzError::Config("Unix domain sockets are not supported on this platform".to_string()))
+    }
+
+    pub(super) async fn connect(_endpoint: &Endpoint, _path: &str) -> Result<Channel, AzError> {
+        Err(AzError::Config("Unix domain sockets are not supported on this platform".to_string()))
+    }
+}
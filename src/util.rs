@@ -0,0 +1,39 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, MutexGuard};
+
+/// Returns a random number, good enough for jitter and load balancing but not for cryptography.
+///
+/// Each `RandomState` is seeded with fresh per-process random keys, so hashing nothing with it
+/// yields a different value on every call without pulling in a random number generator crate.
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Returns a random number in `[0, 1]`.
+pub(crate) fn random_unit() -> f64 {
+    random_u64() as f64 / u64::MAX as f64
+}
+
+/// Locks the mutex, recovering the data if another thread panicked while holding it.
+///
+/// The SDK only keeps counters and caches behind its mutexes, which stay usable after a panic.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
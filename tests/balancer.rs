@@ -0,0 +1,94 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use permguard::az_client::AzClient;
+use permguard::config::{AzConfig, BalancingStrategy, EjectionPolicy};
+use permguard::error::AzError;
+use common::{closed_port, endpoint, request, spawn_blackhole, spawn_tcp, MockPdp};
+
+#[tokio::test]
+async fn ejects_a_replica_whose_call_hangs_until_the_deadline() {
+    let first = MockPdp::allowing();
+    let second = MockPdp::allowing();
+    let endpoints = vec![
+        endpoint(spawn_tcp(first.clone(), None).await),
+        endpoint(spawn_tcp(second.clone(), None).await),
+    ];
+    let config = AzConfig::new()
+        .with_endpoints(endpoints)
+        .with_balancing_strategy(BalancingStrategy::RoundRobin)
+        .with_ejection_policy(EjectionPolicy::new().with_consecutive_failures(1))
+        .with_timeout(Some(Duration::from_millis(100)));
+
+    // The first call hangs before reaching the PDP, so only the client deadline can end it.
+    let tokens = Arc::new(AtomicUsize::new(0));
+    let client = AzClient::new(config).with_token_provider(move || {
+        let hang = tokens.fetch_add(1, Ordering::SeqCst) == 0;
+        async move {
+            if hang {
+                std::future::pending::<()>().await;
+            }
+            Ok("token".to_string())
+        }
+    });
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Timeout(_)), "unexpected error: {:?}", err);
+    for _ in 0..4 {
+        assert!(client.check_auth(Some(request())).await.unwrap().decision);
+    }
+    assert_eq!(first.calls(), 0);
+    assert_eq!(second.calls(), 4);
+}
+
+#[tokio::test]
+async fn ejects_a_replica_that_does_not_answer() {
+    let healthy = MockPdp::allowing();
+    let endpoints = vec![endpoint(spawn_blackhole().await), endpoint(spawn_tcp(healthy.clone(), None).await)];
+    let config = AzConfig::new()
+        .with_endpoints(endpoints)
+        .with_ejection_policy(EjectionPolicy::new().with_consecutive_failures(1))
+        .with_timeout(Some(Duration::from_millis(100)));
+    let client = AzClient::new(config);
+
+    let mut timeouts = 0;
+    for _ in 0..5 {
+        match client.check_auth(Some(request())).await {
+            Ok(response) => assert!(response.decision),
+            Err(AzError::Timeout(_)) => timeouts += 1,
+            Err(err) => panic!("unexpected error: {:?}", err),
+        }
+    }
+    assert_eq!(timeouts, 1);
+    assert_eq!(healthy.calls(), 4);
+}
+
+#[tokio::test]
+async fn fails_over_from_an_unreachable_replica() {
+    let healthy = MockPdp::allowing();
+    let endpoints = vec![endpoint(closed_port()), endpoint(spawn_tcp(healthy.clone(), None).await)];
+    let client = AzClient::new(AzConfig::new().with_endpoints(endpoints));
+
+    for _ in 0..3 {
+        assert!(client.check_auth(Some(request())).await.unwrap().decision);
+    }
+    assert_eq!(healthy.calls(), 3);
+}
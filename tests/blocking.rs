@@ -0,0 +1,67 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+use permguard::blocking::AzClient;
+use permguard::config::AzConfig;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+/// Serves the mock PDP from its own runtime, as a separate process would.
+fn spawn_server(pdp: MockPdp) -> std::net::SocketAddr {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            sender.send(spawn_tcp(pdp, None).await).unwrap();
+            std::future::pending::<()>().await
+        });
+    });
+    receiver.recv().unwrap()
+}
+
+#[test]
+fn checks_without_an_async_runtime() {
+    let addr = spawn_server(MockPdp::allowing());
+    let client = AzClient::connect(AzConfig::new().with_endpoint(Some(endpoint(addr)))).unwrap();
+
+    assert!(client.check_auth(Some(request())).unwrap().decision);
+}
+
+#[test]
+fn runs_checks_from_several_threads_concurrently() {
+    let delay = Duration::from_millis(300);
+    let addr = spawn_server(MockPdp { delay, ..MockPdp::allowing() });
+    let client = AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr)))).unwrap();
+    client.check_auth(Some(request())).unwrap();
+
+    let started = Instant::now();
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            std::thread::spawn(move || client.check_auth(Some(request())).unwrap().decision)
+        })
+        .collect();
+    for thread in threads {
+        assert!(thread.join().unwrap());
+    }
+
+    // Serialized calls would take 8 times the delay.
+    assert!(started.elapsed() < delay * 4, "took {:?}", started.elapsed());
+}
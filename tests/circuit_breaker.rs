@@ -0,0 +1,86 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use std::time::Duration;
+use permguard::az_client::AzClient;
+use permguard::circuit::CircuitState;
+use permguard::config::{AzConfig, CircuitBreakerConfig, RetryPolicy};
+use permguard::error::AzError;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+fn config(pdp: std::net::SocketAddr) -> AzConfig {
+    let breaker = CircuitBreakerConfig::new()
+        .with_window_size(2)
+        .with_failure_rate_threshold(0.5)
+        .with_open_duration(Duration::from_millis(100))
+        .with_half_open_probes(1);
+    AzConfig::new()
+        .with_endpoint(Some(endpoint(pdp)))
+        .with_timeout(Some(Duration::from_millis(100)))
+        .with_circuit_breaker(Some(breaker))
+}
+
+#[tokio::test]
+async fn opens_when_the_pdp_hangs_until_the_deadline() {
+    let pdp = MockPdp::hanging();
+    let client = AzClient::new(config(spawn_tcp(pdp.clone(), None).await));
+
+    for _ in 0..2 {
+        let err = client.check_auth(Some(request())).await.unwrap_err();
+        assert!(matches!(err, AzError::Timeout(_)), "unexpected error: {:?}", err);
+    }
+    assert_eq!(client.circuit_state(), Some(CircuitState::Open));
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::CircuitOpen), "unexpected error: {:?}", err);
+    assert_eq!(pdp.calls(), 2);
+}
+
+#[tokio::test]
+async fn reopens_when_a_half_open_probe_hangs_until_the_deadline() {
+    let pdp = MockPdp::hanging();
+    let client = AzClient::new(config(spawn_tcp(pdp.clone(), None).await));
+    for _ in 0..2 {
+        let _ = client.check_auth(Some(request())).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(client.circuit_state(), Some(CircuitState::HalfOpen));
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Timeout(_)), "unexpected error: {:?}", err);
+    assert_eq!(client.circuit_state(), Some(CircuitState::Open));
+    assert_eq!(pdp.calls(), 3);
+}
+
+#[tokio::test]
+async fn opens_when_the_deadline_expires_during_retry_backoff() {
+    let pdp = MockPdp { status: Some(tonic::Code::Unavailable), ..MockPdp::default() };
+    let retry = RetryPolicy::new()
+        .with_max_attempts(3)
+        .with_base_backoff(Duration::from_secs(60))
+        .with_jitter(0.0);
+    let client = AzClient::new(config(spawn_tcp(pdp.clone(), None).await).with_retry_policy(Some(retry)));
+
+    for _ in 0..2 {
+        let err = client.check_auth(Some(request())).await.unwrap_err();
+        assert!(matches!(err, AzError::Timeout(_)), "unexpected error: {:?}", err);
+    }
+    assert_eq!(client.circuit_state(), Some(CircuitState::Open));
+    assert_eq!(pdp.calls(), 2);
+}
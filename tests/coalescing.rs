@@ -0,0 +1,95 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use std::time::Duration;
+use permguard::az_client::AzClient;
+use permguard::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+use permguard::config::AzConfig;
+use permguard::error::AzError;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+async fn client(pdp: MockPdp) -> AzClient {
+    let addr = spawn_tcp(pdp, None).await;
+    AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr))).with_request_coalescing(true))
+}
+
+fn slow(pdp: MockPdp) -> MockPdp {
+    MockPdp { delay: Duration::from_millis(200), ..pdp }
+}
+
+#[tokio::test]
+async fn sends_concurrent_identical_checks_once() {
+    let pdp = slow(MockPdp::allowing());
+    let client = client(pdp.clone()).await;
+
+    let checks: Vec<_> = (0..16)
+        .map(|i| {
+            let client = client.clone();
+            let mut request = request();
+            request.request_id = Some(format!("r{}", i));
+            tokio::spawn(async move { client.check_auth(Some(request)).await })
+        })
+        .collect();
+
+    for (i, check) in checks.into_iter().enumerate() {
+        let response = check.await.unwrap().unwrap();
+        assert!(response.decision);
+        assert_eq!(response.request_id, format!("r{}", i));
+    }
+    assert_eq!(pdp.calls(), 1);
+}
+
+#[tokio::test]
+async fn does_not_merge_different_checks() {
+    let pdp = slow(MockPdp::allowing());
+    let client = client(pdp.clone()).await;
+
+    let checks: Vec<_> = ["d1", "d2", "d3"]
+        .into_iter()
+        .map(|resource_id| {
+            let client = client.clone();
+            let request = AzAtomicRequestBuilder::new(1, "ledger", "amy", "App::Document", "App::Action::view")
+                .with_resource_id(resource_id)
+                .build();
+            tokio::spawn(async move { client.check_auth(Some(request)).await })
+        })
+        .collect();
+
+    for check in checks {
+        assert!(check.await.unwrap().unwrap().decision);
+    }
+    assert_eq!(pdp.calls(), 3);
+}
+
+#[tokio::test]
+async fn shares_errors_with_every_caller() {
+    let pdp = slow(MockPdp { status: Some(tonic::Code::PermissionDenied), ..MockPdp::default() });
+    let client = client(pdp.clone()).await;
+
+    let checks: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.check_auth(Some(request())).await })
+        })
+        .collect();
+
+    for check in checks {
+        let err = check.await.unwrap().unwrap_err();
+        assert!(matches!(err, AzError::Status { code: tonic::Code::PermissionDenied, .. }), "unexpected error: {:?}", err);
+    }
+    assert_eq!(pdp.calls(), 1);
+}
@@ -0,0 +1,206 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! A mock PDP and helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use permguard::az_client::policydecisionpoint as pdp;
+use permguard::az_req::az_atomic_request_builder::AzAtomicRequestBuilder;
+use permguard::az_req::model::AzRequest;
+use permguard::config::AzEndpoint;
+use tokio_stream::wrappers::{TcpListenerStream, UnixListenerStream};
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Server, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+
+/// A PDP answering every evaluation with the same decision.
+#[derive(Debug, Clone, Default)]
+pub struct MockPdp {
+    pub calls: Arc<AtomicUsize>,
+    pub decision: bool,
+    /// Delay before answering; a long delay simulates a hung PDP.
+    pub delay: Duration,
+    /// Status returned instead of a decision.
+    pub status: Option<tonic::Code>,
+    /// Metadata of every request received.
+    pub metadata: Arc<Mutex<Vec<MetadataMap>>>,
+}
+
+impl MockPdp {
+    pub fn allowing() -> Self {
+        Self { decision: true, ..Self::default() }
+    }
+
+    pub fn hanging() -> Self {
+        Self { delay: Duration::from_secs(3600), ..Self::default() }
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Returns the values of a metadata header, one entry per request received.
+    pub fn header(&self, name: &str) -> Vec<Vec<String>> {
+        self.metadata
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| m.get_all(name).iter().map(|v| v.to_str().unwrap().to_string()).collect())
+            .collect()
+    }
+}
+
+#[tonic::async_trait]
+impl pdp::v1pdp_service_server::V1pdpService for MockPdp {
+    async fn authorization_check(
+        &self,
+        request: Request<pdp::AuthorizationCheckRequest>,
+    ) -> Result<Response<pdp::AuthorizationCheckResponse>, Status> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.metadata.lock().unwrap().push(request.metadata().clone());
+        tokio::time::sleep(self.delay).await;
+        if let Some(code) = self.status {
+            return Err(Status::new(code, "mock failure"));
+        }
+
+        let request = request.into_inner();
+        let evaluations = request
+            .evaluations
+            .iter()
+            .map(|e| pdp::EvaluationResponse {
+                request_id: e.request_id.clone(),
+                decision: self.decision,
+                context: None,
+            })
+            .collect();
+        Ok(Response::new(pdp::AuthorizationCheckResponse {
+            request_id: request.request_id,
+            decision: self.decision,
+            context: None,
+            evaluations,
+        }))
+    }
+}
+
+/// Serves the mock PDP on a local TCP port, optionally over TLS.
+pub async fn spawn_tcp(pdp: MockPdp, tls: Option<ServerTlsConfig>) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut server = Server::builder();
+    if let Some(tls) = tls {
+        server = server.tls_config(tls).unwrap();
+    }
+    tokio::spawn(
+        server
+            .add_service(pdp::v1pdp_service_server::V1pdpServiceServer::new(pdp))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    addr
+}
+
+/// Serves the mock PDP on a Unix domain socket.
+pub fn spawn_unix(pdp: MockPdp, path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let listener = tokio::net::UnixListener::bind(path).unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(pdp::v1pdp_service_server::V1pdpServiceServer::new(pdp))
+            .serve_with_incoming(UnixListenerStream::new(listener)),
+    );
+}
+
+/// Accepts connections and never answers, like a hung PDP that ignores the propagated deadline.
+pub async fn spawn_blackhole() -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    addr
+}
+
+/// Returns the address of a local port nobody listens on.
+pub fn closed_port() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+/// Returns a plain-text endpoint for a local address.
+pub fn endpoint(addr: SocketAddr) -> AzEndpoint {
+    AzEndpoint::try_new("http", addr.port(), "127.0.0.1").unwrap()
+}
+
+/// Returns a valid atomic authorization request.
+pub fn request() -> AzRequest {
+    AzAtomicRequestBuilder::new(
+        273165098782,
+        "fd1ac44e4afa4fc4beec622494d3175a",
+        "amy.smith@acmecorp.com",
+        "MagicFarmacia::Platform::Subscription",
+        "MagicFarmacia::Platform::Action::create",
+    )
+    .with_request_id("1234")
+    .with_resource_id("e3a786fd07e24bfa95ba4341d3695ae8")
+    .build()
+}
+
+/// A test CA with a server certificate for `pdp.internal` and `localhost`, and a client certificate.
+pub struct TestPki {
+    pub ca_pem: String,
+    pub server_cert_pem: String,
+    pub server_key_pem: String,
+    pub client_cert_pem: String,
+    pub client_key_pem: String,
+}
+
+impl TestPki {
+    pub fn generate() -> Self {
+        use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, IsCa, KeyPair};
+
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = CertifiedIssuer::self_signed(ca_params, KeyPair::generate().unwrap()).unwrap();
+
+        let server_key = KeyPair::generate().unwrap();
+        let server_cert = CertificateParams::new(vec!["pdp.internal".to_string(), "localhost".to_string()])
+            .unwrap()
+            .signed_by(&server_key, &ca)
+            .unwrap();
+
+        let client_key = KeyPair::generate().unwrap();
+        let client_cert = CertificateParams::new(vec!["client".to_string()])
+            .unwrap()
+            .signed_by(&client_key, &ca)
+            .unwrap();
+
+        Self {
+            ca_pem: ca.pem(),
+            server_cert_pem: server_cert.pem(),
+            server_key_pem: server_key.serialize_pem(),
+            client_cert_pem: client_cert.pem(),
+            client_key_pem: client_key.serialize_pem(),
+        }
+    }
+}
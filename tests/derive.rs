@@ -0,0 +1,119 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+#![cfg(feature = "derive")]
+
+use std::collections::HashMap;
+use permguard::az_req::az_action::AzAction;
+use permguard::az_req::model::{CedarEntity, EntityUid, Resource, Subject};
+use permguard::error::AzError;
+use permguard::{AzAction, AzResource, AzSubject, CedarEntity};
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Serialize, AzResource, CedarEntity)]
+#[az(type = "Document", namespace = "Acme::Docs")]
+struct Document {
+    #[az(id)]
+    document_id: String,
+    #[az(rename = "name")]
+    title: String,
+    #[az(skip)]
+    #[allow(dead_code)]
+    body: String,
+    #[az(parent = "Folder")]
+    folder_id: Option<String>,
+    #[az(parent = "Acme::Teams::Team")]
+    teams: Vec<u32>,
+}
+
+#[derive(Serialize, AzSubject)]
+#[az(source = "keycloak")]
+struct Employee {
+    id: String,
+    scores: HashMap<(u8, u8), u8>,
+}
+
+#[derive(AzAction)]
+#[az(namespace = "Acme::Docs", rename_all = "kebab-case")]
+enum DocumentAction {
+    ShareLink,
+    #[az(rename = "view")]
+    Read,
+}
+
+fn document() -> Document {
+    Document {
+        document_id: "d1".to_string(),
+        title: "Plan".to_string(),
+        body: "secret".to_string(),
+        folder_id: Some("f1".to_string()),
+        teams: vec![7, 8],
+    }
+}
+
+#[test]
+fn converts_a_resource() {
+    let resource = Resource::try_from(&document()).unwrap();
+
+    assert_eq!(resource.r#type, "Acme::Docs::Document");
+    assert_eq!(resource.id, "d1");
+    assert_eq!(resource.properties, Some(HashMap::from([("name".to_string(), json!("Plan"))])));
+}
+
+#[test]
+fn converts_an_entity_with_parents() {
+    let entity = CedarEntity::try_from(document()).unwrap();
+
+    assert_eq!(entity.uid, EntityUid::new("Acme::Docs::Document", "d1"));
+    assert_eq!(entity.attrs, HashMap::from([("name".to_string(), json!("Plan"))]));
+    assert_eq!(
+        entity.parents,
+        [
+            EntityUid::new("Acme::Docs::Folder", "f1"),
+            EntityUid::new("Acme::Teams::Team", "7"),
+            EntityUid::new("Acme::Teams::Team", "8"),
+        ]
+    );
+}
+
+#[test]
+fn reports_fields_that_cannot_be_serialized() {
+    let employee = Employee { id: "amy".to_string(), scores: HashMap::from([((1, 2), 3)]) };
+
+    match Subject::try_from(&employee) {
+        Err(AzError::InvalidRequest(message)) => {
+            assert!(message.starts_with("cannot serialize the `scores` field of `Employee`"), "{}", message)
+        }
+        other => panic!("expected a serialization error, got {:?}", other.map(|s| s.properties)),
+    }
+
+    let employee = Employee { id: "amy".to_string(), scores: HashMap::new() };
+    let subject = Subject::try_from(employee).unwrap();
+    assert_eq!((subject.r#type.as_str(), subject.source.as_deref()), ("user", Some("keycloak")));
+}
+
+#[test]
+fn names_actions() {
+    assert_eq!(DocumentAction::ShareLink.namespace(), "Acme::Docs");
+    assert_eq!(DocumentAction::ShareLink.name(), "share-link");
+    assert_eq!(DocumentAction::Read.name(), "view");
+}
+
+#[test]
+fn rejects_invalid_attributes() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use permguard::az_client::AzClient;
+use permguard::az_req::action_builder::ActionBuilder;
+use permguard::az_req::az_request_builder::AzRequestBuilder;
+use permguard::az_req::evaluation_builder::EvaluationBuilder;
+use permguard::az_req::model::{AzResponse, ContextResponse};
+use permguard::az_req::resource_builder::ResourceBuilder;
+use permguard::az_req::subject_builder::SubjectBuilder;
+use permguard::config::failure_default::{REASON_CODE, USER_MESSAGE};
+use permguard::config::{AzConfig, AzTlsConfig, FailureMode};
+use permguard::error::AzError;
+use common::{closed_port, endpoint, request};
+
+/// Checks that a synthesized decision carries the `PDP_UNREACHABLE` reasons.
+fn assert_unreachable(context: &Option<ContextResponse>, id: &str) {
+    let context = context.as_ref().expect("a synthesized decision has a context");
+    assert_eq!(context.id, id);
+    let admin = context.reason_admin.as_ref().unwrap();
+    assert_eq!(admin.code, "PDP_UNREACHABLE");
+    assert!(!admin.message.is_empty());
+    let user = context.reason_user.as_ref().unwrap();
+    assert_eq!((user.code.as_str(), user.message.as_str()), (REASON_CODE, USER_MESSAGE));
+}
+
+fn assert_synthesized(response: &AzResponse, decision: bool) {
+    assert_eq!(response.decision, decision);
+    assert_unreachable(&response.context, &response.request_id);
+    for evaluation in &response.evaluations {
+        assert_eq!(evaluation.decision, decision);
+        assert_unreachable(&evaluation.context, &evaluation.request_id);
+    }
+}
+
+#[tokio::test]
+async fn fail_open_permits_when_the_pdp_is_unreachable() {
+    let config = AzConfig::new()
+        .with_endpoint(Some(endpoint(closed_port())))
+        .with_failure_mode(FailureMode::FailOpen);
+
+    let response = AzClient::new(config).check_auth(Some(request())).await.unwrap();
+    assert_eq!(response.request_id, "1234");
+    assert_synthesized(&response, true);
+}
+
+#[tokio::test]
+async fn fail_open_does_not_permit_on_an_invalid_tls_identity() {
+    let tls = AzTlsConfig::new().with_identity_pem("not a certificate", "not a key");
+    let config = AzConfig::new()
+        .with_endpoint(Some(endpoint(closed_port())))
+        .with_tls(Some(tls))
+        .with_failure_mode(FailureMode::FailOpen);
+    let client = AzClient::new(config);
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Config(_)), "unexpected error: {:?}", err);
+    assert!(!err.is_unavailable());
+}
+
+#[tokio::test]
+async fn fail_closed_denies_when_the_pdp_is_unreachable() {
+    let config = AzConfig::new()
+        .with_endpoint(Some(endpoint(closed_port())))
+        .with_failure_mode(FailureMode::FailClosed);
+
+    let response = AzClient::new(config).check_auth(Some(request())).await.unwrap();
+    assert_eq!(response.request_id, "1234");
+    assert_synthesized(&response, false);
+}
+
+#[tokio::test]
+async fn fail_closed_denies_every_evaluation_with_its_request_id() {
+    let config = AzConfig::new()
+        .with_endpoint(Some(endpoint(closed_port())))
+        .with_failure_mode(FailureMode::FailClosed);
+    let evaluation = |id: &str| {
+        EvaluationBuilder::new(Some(SubjectBuilder::new("amy").build()), None, None)
+            .with_request_id(id)
+            .build()
+    };
+    let request = AzRequestBuilder::new(1, "ledger")
+        .with_request_id(Some("batch".to_string()))
+        .with_resource(Some(ResourceBuilder::new("App::Document").with_id("d1").build()))
+        .with_action(Some(ActionBuilder::new("App::Action::view").build()))
+        .with_evaluation(evaluation("e1"))
+        .with_evaluation(evaluation("e2"))
+        .build();
+
+    let response = AzClient::new(config).check_auth(Some(request)).await.unwrap();
+    let ids: Vec<&str> = response.evaluations.iter().map(|e| e.request_id.as_str()).collect();
+    assert_eq!(ids, ["e1", "e2"]);
+    assert_synthesized(&response, false);
+}
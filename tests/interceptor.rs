@@ -0,0 +1,118 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use permguard::az_client::AzClient;
+use permguard::config::AzConfig;
+use permguard::error::AzError;
+use tonic::metadata::MetadataMap;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+async fn config(pdp: &MockPdp) -> AzConfig {
+    let addr = spawn_tcp(pdp.clone(), None).await;
+    AzConfig::new().with_endpoint(Some(endpoint(addr)))
+}
+
+#[tokio::test]
+async fn sends_static_headers() {
+    let pdp = MockPdp::allowing();
+    let config = config(&pdp).await.with_header("x-tenant", "acme").with_header("x-scope", "a").with_header("x-scope", "b");
+
+    AzClient::new(config).check_auth(Some(request())).await.unwrap();
+    assert_eq!(pdp.header("x-tenant"), [["acme"]]);
+    assert_eq!(pdp.header("x-scope"), [["a", "b"]]);
+}
+
+#[tokio::test]
+async fn asks_for_a_fresh_token_on_every_call() {
+    let pdp = MockPdp::allowing();
+    let tokens = Arc::new(AtomicUsize::new(0));
+    let provider = {
+        let tokens = tokens.clone();
+        move || {
+            let token = format!("token-{}", tokens.fetch_add(1, Ordering::SeqCst) + 1);
+            async move { Ok(token) }
+        }
+    };
+    let client = AzClient::new(config(&pdp).await).with_token_provider(provider);
+
+    client.check_auth(Some(request())).await.unwrap();
+    client.check_auth(Some(request())).await.unwrap();
+    assert_eq!(pdp.header("authorization"), [["Bearer token-1"], ["Bearer token-2"]]);
+}
+
+#[tokio::test]
+async fn runs_interceptors_in_order_after_headers_and_token() {
+    let pdp = MockPdp::allowing();
+    let append = |step: &'static str| {
+        move |metadata: &mut MetadataMap| {
+            let seen = metadata.get("x-trace").map(|v| v.to_str().unwrap().to_string());
+            let trace = match seen {
+                Some(seen) => format!("{},{}", seen, step),
+                None => step.to_string(),
+            };
+            metadata.insert("x-trace", trace.parse().unwrap());
+            Ok(())
+        }
+    };
+    let client = AzClient::new(config(&pdp).await.with_header("x-trace", "header"))
+        .with_token_provider(|| async { Ok("secret".to_string()) })
+        .with_interceptor(append("first"))
+        .with_interceptor(|metadata: &mut MetadataMap| {
+            assert_eq!(metadata.get("authorization").unwrap(), "Bearer secret");
+            metadata.insert("authorization", "Bearer replaced".parse().unwrap());
+            Ok(())
+        })
+        .with_interceptor(append("second"));
+
+    client.check_auth(Some(request())).await.unwrap();
+    assert_eq!(pdp.header("x-trace"), [["header,first,second"]]);
+    assert_eq!(pdp.header("authorization"), [["Bearer replaced"]]);
+}
+
+#[tokio::test]
+async fn does_not_send_the_request_when_an_interceptor_fails() {
+    let pdp = MockPdp::allowing();
+    let client = AzClient::new(config(&pdp).await)
+        .with_interceptor(|_: &mut MetadataMap| Err(AzError::InvalidToken("no session".to_string())));
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(&err, AzError::InvalidToken(message) if message == "no session"), "unexpected error: {:?}", err);
+    assert_eq!(pdp.calls(), 0);
+}
+
+#[tokio::test]
+async fn does_not_send_the_request_when_the_token_provider_fails() {
+    let pdp = MockPdp::allowing();
+    let client = AzClient::new(config(&pdp).await)
+        .with_token_provider(|| async { Err(AzError::InvalidToken("expired".to_string())) });
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::InvalidToken(_)), "unexpected error: {:?}", err);
+    assert_eq!(pdp.calls(), 0);
+}
+
+#[tokio::test]
+async fn reports_invalid_headers_as_configuration_errors() {
+    let pdp = MockPdp::allowing();
+    let config = config(&pdp).await.with_header("bad header", "value");
+
+    let err = AzClient::new(config).check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Config(_)), "unexpected error: {:?}", err);
+    assert_eq!(pdp.calls(), 0);
+}
@@ -0,0 +1,174 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "jwt")]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde_json::{json, Value};
+use permguard::az_req::jwt::{ClaimMapping, JwtDecoder};
+use permguard::error::AzError;
+
+const JWKS: &str = include_str!("fixtures/jwt/jwks.json");
+const RSA_KEY: &[u8] = include_bytes!("fixtures/jwt/rsa_key.pem");
+
+fn claims() -> Value {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    json!({
+        "sub": "amy.smith@acmecorp.com",
+        "iss": "https://idp.acmecorp.com",
+        "aud": "permguard",
+        "exp": now + 3600,
+        "realm_access": { "roles": ["admin", "auditor"] },
+        "department": "finance",
+    })
+}
+
+fn sign(algorithm: Algorithm, kid: Option<&str>, claims: &Value) -> String {
+    let mut header = Header::new(algorithm);
+    header.kid = kid.map(str::to_string);
+    let key = match algorithm {
+        Algorithm::HS256 => EncodingKey::from_secret(b"secret"),
+        _ => EncodingKey::from_rsa_pem(RSA_KEY).unwrap(),
+    };
+    encode(&header, claims, &key).unwrap()
+}
+
+fn decoder() -> JwtDecoder {
+    JwtDecoder::from_jwks_json(JWKS).unwrap()
+}
+
+fn invalid_token(result: Result<impl std::fmt::Debug, AzError>) -> String {
+    match result {
+        Err(AzError::InvalidToken(reason)) => reason,
+        other => panic!("expected an invalid token, got {:?}", other),
+    }
+}
+
+#[test]
+fn decodes_a_token_signed_with_a_jwks_key() {
+    let token = sign(Algorithm::RS256, Some("k1"), &claims());
+
+    let claims = decoder()
+        .with_issuer("https://idp.acmecorp.com")
+        .with_audience("permguard")
+        .decode(&token)
+        .unwrap();
+    let principal = claims.principal().unwrap().build();
+
+    assert_eq!(principal.id, "amy.smith@acmecorp.com");
+    assert_eq!(principal.source.as_deref(), Some("https://idp.acmecorp.com"));
+    assert_eq!(principal.identity_token.as_deref(), Some(token.as_str()));
+}
+
+#[test]
+fn rejects_an_unknown_key_id() {
+    let token = sign(Algorithm::RS256, Some("k2"), &claims());
+
+    assert!(invalid_token(decoder().decode(&token)).contains("no matching key"));
+}
+
+#[test]
+fn rejects_hmac_tokens() {
+    let token = sign(Algorithm::HS256, Some("k1"), &claims());
+
+    assert!(invalid_token(decoder().decode(&token)).contains("HS256"));
+}
+
+#[test]
+fn rejects_an_algorithm_other_than_the_key_algorithm() {
+    // PS256 verifies with the same RSA key, but the JWK is declared for RS256 only.
+    let token = sign(Algorithm::PS256, Some("k1"), &claims());
+
+    assert!(invalid_token(decoder().decode(&token)).contains("does not match the key algorithm"));
+}
+
+#[test]
+fn rejects_a_tampered_signature() {
+    let token = sign(Algorithm::RS256, Some("k1"), &claims());
+    let forged = sign(Algorithm::RS256, Some("k1"), &json!({ "sub": "mallory", "exp": claims()["exp"] }));
+    let (header_and_claims, _) = forged.rsplit_once('.').unwrap();
+    let (_, signature) = token.rsplit_once('.').unwrap();
+
+    invalid_token(decoder().decode(&format!("{}.{}", header_and_claims, signature)));
+}
+
+#[test]
+fn rejects_the_wrong_issuer() {
+    let token = sign(Algorithm::RS256, Some("k1"), &claims());
+
+    invalid_token(decoder().with_issuer("https://other.example").decode(&token));
+}
+
+#[test]
+fn rejects_the_wrong_audience() {
+    let token = sign(Algorithm::RS256, Some("k1"), &claims());
+
+    invalid_token(decoder().with_audience("another-service").decode(&token));
+}
+
+#[test]
+fn rejects_an_expired_token() {
+    let mut expired = claims();
+    expired["exp"] = json!(1_000_000_000);
+    let token = sign(Algorithm::RS256, Some("k1"), &expired);
+
+    assert!(invalid_token(decoder().decode(&token)).contains("Expired"));
+}
+
+#[test]
+fn maps_dotted_claims_to_subject_properties() {
+    let token = sign(Algorithm::RS256, Some("k1"), &claims());
+    let mapping = ClaimMapping::new()
+        .with_source_claim(None)
+        .with_subject_property("realm_access.roles", "roles")
+        .with_subject_property("department", "department")
+        .with_subject_property("missing.claim", "missing");
+
+    let claims = decoder().with_mapping(mapping).decode(&token).unwrap();
+    let subject = claims.subject().unwrap().build();
+    let properties = subject.properties.unwrap();
+
+    assert_eq!(claims.claim("realm_access.roles"), Some(&json!(["admin", "auditor"])));
+    assert_eq!(subject.id, "amy.smith@acmecorp.com");
+    assert_eq!(subject.source, None);
+    assert_eq!(properties["roles"], json!(["admin", "auditor"]));
+    assert_eq!(properties["department"], json!("finance"));
+    assert!(!properties.contains_key("missing"));
+}
+
+#[test]
+fn maps_the_id_from_a_nested_claim() {
+    let mut nested = claims();
+    nested["user"] = json!({ "email": "amy@acmecorp.com" });
+    let token = sign(Algorithm::RS256, Some("k1"), &nested);
+    let mapping = ClaimMapping::new().with_id_claim("user.email");
+
+    let claims = decoder().with_mapping(mapping).decode(&token).unwrap();
+    assert_eq!(claims.principal().unwrap().build().id, "amy@acmecorp.com");
+
+    let mapping = ClaimMapping::new().with_id_claim("realm_access");
+    let claims = decoder().with_mapping(mapping).decode(&token).unwrap();
+    assert!(invalid_token(claims.subject().map(|s| s.build())).contains("not a string"));
+}
+
+#[test]
+fn decodes_without_verification_when_asked_to() {
+    let token = sign(Algorithm::HS256, None, &claims());
+
+    let claims = JwtDecoder::unverified().decode(&token).unwrap();
+    assert_eq!(claims.claim("sub"), Some(&json!("amy.smith@acmecorp.com")));
+}
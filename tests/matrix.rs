@@ -0,0 +1,63 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use permguard::az_client::AzClient;
+use permguard::az_req::action_builder::ActionBuilder;
+use permguard::az_req::matrix_builder::MatrixBuilder;
+use permguard::az_req::resource_builder::ResourceBuilder;
+use permguard::az_req::subject_builder::SubjectBuilder;
+use permguard::config::AzConfig;
+use permguard::error::AzError;
+use common::{endpoint, spawn_tcp, MockPdp};
+
+async fn client(pdp: &MockPdp) -> AzClient {
+    let addr = spawn_tcp(pdp.clone(), None).await;
+    AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr))))
+}
+
+#[tokio::test]
+async fn checks_every_combination_in_one_request() {
+    let pdp = MockPdp::allowing();
+    let matrix = MatrixBuilder::new(273165098782, "fd1ac44e4afa4fc4beec622494d3175a")
+        .with_request_id("batch")
+        .with_subjects(["amy", "ben"].map(|id| SubjectBuilder::new(id).build()))
+        .with_resources(["d1", "d2"].map(|id| ResourceBuilder::new("App::Document").with_id(id).build()))
+        .with_actions(["App::Action::view", "App::Action::edit", "App::Action::share"].map(|name| ActionBuilder::new(name).build()))
+        .build();
+
+    let decisions = client(&pdp).await.check_matrix(matrix).await.unwrap();
+
+    assert_eq!(pdp.calls(), 1);
+    assert_eq!(decisions.response().request_id, "batch");
+    assert_eq!(decisions.response().evaluations.len(), 12);
+    assert_eq!(decisions.evaluation(1, 0, 2).unwrap().request_id, "batch:1:0:2");
+    assert_eq!(decisions.decision_for("ben", "App::Document", "d2", "App::Action::share"), Some(true));
+    assert_eq!(decisions.allowed().count(), 12);
+}
+
+#[tokio::test]
+async fn rejects_an_empty_matrix_without_calling_the_pdp() {
+    let pdp = MockPdp::allowing();
+    let matrix = MatrixBuilder::new(273165098782, "fd1ac44e4afa4fc4beec622494d3175a")
+        .with_subject(SubjectBuilder::new("amy").build())
+        .with_action(ActionBuilder::new("App::Action::view").build())
+        .build();
+
+    let err = client(&pdp).await.check_matrix(matrix).await.unwrap_err();
+    assert!(matches!(err, AzError::InvalidRequest(_)), "unexpected error: {:?}", err);
+    assert_eq!(pdp.calls(), 0);
+}
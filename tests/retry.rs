@@ -0,0 +1,96 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use std::time::{Duration, Instant};
+use permguard::az_client::AzClient;
+use permguard::config::{AzConfig, RetryPolicy};
+use permguard::error::AzError;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+async fn client(pdp: &MockPdp, retry: RetryPolicy) -> AzClient {
+    let addr = spawn_tcp(pdp.clone(), None).await;
+    AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr))).with_retry_policy(Some(retry)))
+}
+
+fn failing(code: tonic::Code) -> MockPdp {
+    MockPdp { status: Some(code), ..MockPdp::default() }
+}
+
+#[tokio::test]
+async fn stops_after_max_attempts() {
+    let pdp = failing(tonic::Code::Unavailable);
+    let retry = RetryPolicy::new().with_max_attempts(4).with_base_backoff(Duration::from_millis(1));
+
+    let err = client(&pdp, retry).await.check_auth(Some(request())).await.unwrap_err();
+    match &err {
+        AzError::Retried { attempts, last } => {
+            assert_eq!(*attempts, 4);
+            assert!(matches!(**last, AzError::Status { code: tonic::Code::Unavailable, .. }), "unexpected error: {:?}", last);
+        }
+        other => panic!("expected a retried error, got {:?}", other),
+    }
+    assert_eq!(err.attempts(), 4);
+    assert_eq!(pdp.calls(), 4);
+}
+
+#[tokio::test]
+async fn does_not_retry_other_codes() {
+    let pdp = failing(tonic::Code::PermissionDenied);
+    let retry = RetryPolicy::new().with_max_attempts(4).with_base_backoff(Duration::from_millis(1));
+
+    let err = client(&pdp, retry).await.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Status { code: tonic::Code::PermissionDenied, .. }), "unexpected error: {:?}", err);
+    assert_eq!(err.attempts(), 1);
+    assert_eq!(pdp.calls(), 1);
+}
+
+#[tokio::test]
+async fn retries_the_configured_codes_only() {
+    let retry = RetryPolicy::new()
+        .with_max_attempts(3)
+        .with_base_backoff(Duration::from_millis(1))
+        .with_retryable_codes(vec![tonic::Code::ResourceExhausted]);
+
+    let pdp = failing(tonic::Code::ResourceExhausted);
+    let err = client(&pdp, retry.clone()).await.check_auth(Some(request())).await.unwrap_err();
+    assert_eq!(err.attempts(), 3);
+    assert_eq!(pdp.calls(), 3);
+
+    let pdp = failing(tonic::Code::Unavailable);
+    client(&pdp, retry).await.check_auth(Some(request())).await.unwrap_err();
+    assert_eq!(pdp.calls(), 1);
+}
+
+#[tokio::test]
+async fn caps_the_backoff_between_attempts() {
+    let pdp = failing(tonic::Code::Unavailable);
+    let retry = RetryPolicy::new()
+        .with_max_attempts(4)
+        .with_base_backoff(Duration::from_millis(50))
+        .with_max_backoff(Duration::from_millis(60))
+        .with_jitter(0.0);
+    let client = client(&pdp, retry).await;
+
+    let started = Instant::now();
+    client.check_auth(Some(request())).await.unwrap_err();
+    let elapsed = started.elapsed();
+
+    // Backoffs of 50, 60 and 60 ms instead of 50, 100 and 200 ms without the cap.
+    assert_eq!(pdp.calls(), 4);
+    assert!(elapsed >= Duration::from_millis(170), "retried too early: {:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(340), "backoff not capped: {:?}", elapsed);
+}
@@ -0,0 +1,51 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use std::time::{Duration, Instant};
+use permguard::az_client::{AzCallOptions, AzClient};
+use permguard::config::AzConfig;
+use permguard::error::AzError;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+async fn client(delay: Duration, timeout: Duration) -> AzClient {
+    let pdp = MockPdp { delay, ..MockPdp::allowing() };
+    let addr = spawn_tcp(pdp, None).await;
+    AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr))).with_timeout(Some(timeout)))
+}
+
+#[tokio::test]
+async fn a_shorter_per_call_timeout_overrides_the_configured_one() {
+    let client = client(Duration::from_secs(2), Duration::from_secs(10)).await;
+    let options = AzCallOptions::new().with_timeout(Duration::from_millis(100));
+
+    let started = Instant::now();
+    let err = client.check_auth_with_options(Some(request()), options).await.unwrap_err();
+    assert!(matches!(err, AzError::Timeout(timeout) if timeout == Duration::from_millis(100)), "unexpected error: {:?}", err);
+    assert!(started.elapsed() < Duration::from_secs(1), "timed out after {:?}", started.elapsed());
+}
+
+#[tokio::test]
+async fn a_longer_per_call_timeout_overrides_the_configured_one() {
+    let client = client(Duration::from_millis(200), Duration::from_millis(50)).await;
+
+    let err = client.check_auth(Some(request())).await.unwrap_err();
+    assert!(matches!(err, AzError::Timeout(_)), "unexpected error: {:?}", err);
+
+    let options = AzCallOptions::new().with_timeout(Duration::from_secs(5));
+    let response = client.check_auth_with_options(Some(request()), options).await.unwrap();
+    assert!(response.decision);
+}
@@ -0,0 +1,9 @@
+use permguard::AzAction;
+
+#[derive(AzAction)]
+enum DocumentAction {
+    Create,
+    Share { with: String },
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use permguard::AzResource;
+
+#[derive(serde::Serialize, AzResource)]
+struct Document {
+    title: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use permguard::CedarEntity;
+
+#[derive(serde::Serialize, CedarEntity)]
+struct Document {
+    #[az(id)]
+    document_id: String,
+    #[az(id)]
+    slug: String,
+}
+
+fn main() {}
@@ -0,0 +1,69 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(unix)]
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use permguard::az_client::AzClient;
+use permguard::config::{AzConfig, AzEndpoint};
+use common::{request, spawn_unix, MockPdp};
+
+/// Returns a socket path unique to this process and test.
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("permguard-{}-{}.sock", std::process::id(), name))
+}
+
+fn config(path: &Path) -> AzConfig {
+    let endpoint = AzEndpoint::parse(&format!("unix://{}", path.display())).unwrap();
+    AzConfig::new().with_endpoint(Some(endpoint))
+}
+
+#[tokio::test]
+async fn checks_over_a_lazily_connected_unix_socket() {
+    let path = socket_path("lazy");
+    let pdp = MockPdp::allowing();
+    spawn_unix(pdp.clone(), &path);
+
+    let response = AzClient::new(config(&path)).check_auth(Some(request())).await.unwrap();
+    assert!(response.decision);
+    assert_eq!(response.request_id, "1234");
+    assert_eq!(pdp.calls(), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn checks_over_an_eagerly_connected_unix_socket() {
+    let path = socket_path("eager");
+    let pdp = MockPdp::allowing();
+    spawn_unix(pdp.clone(), &path);
+
+    let client = AzClient::connect(config(&path)).await.unwrap();
+    for _ in 0..2 {
+        assert!(client.check_auth(Some(request())).await.unwrap().decision);
+    }
+    assert_eq!(pdp.calls(), 2);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn fails_to_connect_to_a_missing_socket() {
+    let path = socket_path("missing");
+
+    let err = AzClient::connect(config(&path)).await.unwrap_err();
+    assert!(err.is_unavailable(), "unexpected error: {:?}", err);
+}
@@ -0,0 +1,53 @@
+// Copyright 2025 Nitro Agility S.r.l.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+mod common;
+
+use permguard::az_client::AzClient;
+use permguard::config::AzConfig;
+use permguard::error::AzError;
+use common::{endpoint, request, spawn_tcp, MockPdp};
+
+#[tokio::test]
+async fn rejects_an_invalid_request_before_calling_the_pdp() {
+    let pdp = MockPdp::allowing();
+    let addr = spawn_tcp(pdp.clone(), None).await;
+    let client = AzClient::new(AzConfig::new().with_endpoint(Some(endpoint(addr))));
+    let mut invalid = request();
+    invalid.action = None;
+    invalid.authorization_model.zone_id = 0;
+
+    match client.check_auth(Some(invalid)).await {
+        Err(AzError::Validation(errors)) => {
+            let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+            assert_eq!(paths, ["authorization_model.zone_id", "action"]);
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    assert_eq!(pdp.calls(), 0);
+}
+
+#[tokio::test]
+async fn sends_invalid_requests_when_validation_is_disabled() {
+    let pdp = MockPdp::allowing();
+    let addr = spawn_tcp(pdp.clone(), None).await;
+    let config = AzConfig::new().with_endpoint(Some(endpoint(addr))).with_request_validation(false);
+    let mut invalid = request();
+    invalid.authorization_model.zone_id = 0;
+
+    let response = AzClient::new(config).check_auth(Some(invalid)).await.unwrap();
+    assert!(response.decision);
+    assert_eq!(pdp.calls(), 1);
+}